@@ -18,8 +18,8 @@ use std::io::stdout;
 
 use crossterm::{
     event::{
-        KeyCode, KeyEvent, KeyEventKind, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
-        PushKeyboardEnhancementFlags,
+        KeyCode, KeyEvent, KeyEventKind, KeyModifiers, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     execute,
     terminal::*,
@@ -27,12 +27,22 @@ use crossterm::{
 use fundsp::hacker::*;
 use ratatui::prelude::*;
 
+// Keys of the piano from its lowest semitone: the home row for the white keys
+// and the row above for the black ones
+const PIANO_KEYS: &str = "awsedftgyhujkolp;";
+
 pub struct App {
     pub events: EventHandler,
 }
 
 fn handle_key_event(key_event: KeyEvent) -> Vec<Message> {
+    let control = key_event.modifiers.contains(KeyModifiers::CONTROL);
     let key = match key_event.code {
+        KeyCode::Char('z') if control => Some(Key::Undo),
+        KeyCode::Char('y') if control => Some(Key::Redo),
+        KeyCode::Char('c') if control => Some(Key::Copy),
+        KeyCode::Char('x') if control => Some(Key::Cut),
+        KeyCode::Char('v') if control => Some(Key::Paste),
         KeyCode::Char(' ') => Some(Key::Play),
         KeyCode::Up => Some(Key::Up),
         KeyCode::Down => Some(Key::Down),
@@ -42,6 +52,14 @@ fn handle_key_event(key_event: KeyEvent) -> Vec<Message> {
         KeyCode::Char('c') => Some(Key::Edit),
         KeyCode::Char('x') => Some(Key::Option),
         KeyCode::Char('z') => Some(Key::Shift),
+        KeyCode::Char(c) if PIANO_KEYS.contains(c) => {
+            Some(Key::Note(PIANO_KEYS.find(c).unwrap() as u8))
+        }
+        KeyCode::Char('-') => Some(Key::SemitoneDown),
+        KeyCode::Char('=') => Some(Key::SemitoneUp),
+        KeyCode::Char('[') => Some(Key::OctaveDown),
+        KeyCode::Char(']') => Some(Key::OctaveUp),
+        KeyCode::Char('r') => Some(Key::Record),
         _ => None,
     };
 
@@ -81,6 +99,7 @@ impl App {
         let channels = config.channels as usize;
 
         let (tracker, mut backend) = Tracker::new(sample_rate);
+        let clock = tracker.clock.clone();
 
         let mut state = State::new(tracker);
 
@@ -91,7 +110,8 @@ impl App {
         let stream = device.build_output_stream(
             config,
             move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-                write_data(data, channels, &mut next_value)
+                write_data(data, channels, &mut next_value);
+                clock.advance((data.len() / channels) as u64);
             },
             err_fn,
             None,
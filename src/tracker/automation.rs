@@ -0,0 +1,80 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use fundsp::hacker::*;
+
+pub const NB_POINTS: usize = 32;
+
+fn pack(time: f32, value: f32) -> u64 {
+    ((time.to_bits() as u64) << 32) | value.to_bits() as u64
+}
+
+fn unpack(point: u64) -> (f32, f32) {
+    (
+        f32::from_bits((point >> 32) as u32),
+        f32::from_bits(point as u32),
+    )
+}
+
+fn value_at(points: &[AtomicU64], time: f32) -> f32 {
+    points
+        .iter()
+        .map(|point| unpack(point.load(Ordering::Relaxed)))
+        .filter(|(t, _)| *t <= time)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map_or(0.0, |(_, value)| value)
+}
+
+/// A control of a voice whose changes are scheduled ahead of time by the tracker
+/// and picked up by the audio thread when their time comes. Times are in seconds
+/// since the start of the voice.
+///
+/// Changes are kept in a small ring, the tracker must not schedule more than
+/// `NB_POINTS` of them ahead of the audio thread.
+pub struct Automation {
+    points: Arc<Vec<AtomicU64>>,
+    next: usize,
+    last_time: f64,
+    last: f32,
+}
+
+impl Automation {
+    pub fn new(value: f32) -> Self {
+        let points = (0..NB_POINTS)
+            .map(|i| {
+                let time = if i == 0 { 0.0 } else { f32::INFINITY };
+                AtomicU64::new(pack(time, value))
+            })
+            .collect();
+        Self {
+            points: Arc::new(points),
+            next: 1,
+            last_time: 0.0,
+            last: value,
+        }
+    }
+
+    pub fn set(&mut self, time: f64, value: f32) {
+        if value == self.last {
+            return;
+        }
+        // A second change at the same time replaces the first one
+        if time != self.last_time {
+            self.next = (self.next + 1) % NB_POINTS;
+        }
+        self.last_time = time;
+        self.last = value;
+        let current = (self.next + NB_POINTS - 1) % NB_POINTS;
+        self.points[current].store(pack(time as f32, value), Ordering::Relaxed);
+    }
+
+    /// Unit outputting the value of the control
+    pub fn unit(&self) -> Net {
+        let points = self.points.clone();
+        Net::wrap(Box::new(envelope(move |t| {
+            value_at(&points, t as f32) as f64
+        })))
+    }
+}
@@ -1,3 +1,19 @@
+/// A phrase and the number of semitones its notes are transposed by
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct ChainEntry {
+    pub phrase: usize,
+    pub transpose: i32,
+}
+
+#[derive(PartialEq, Clone)]
 pub struct Chain {
-    pub phrases: Vec<Option<usize>>,
+    pub phrases: Vec<Option<ChainEntry>>,
+}
+
+impl Chain {
+    pub fn new() -> Self {
+        Self {
+            phrases: std::iter::repeat_with(|| None).take(16).collect(),
+        }
+    }
 }
@@ -0,0 +1,30 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Count of the samples rendered by the audio thread. It starts with the
+/// sequencers, so its time is the time of the sequencers.
+#[derive(Clone)]
+pub struct Clock {
+    samples: Arc<AtomicU64>,
+    sample_rate: f64,
+}
+
+impl Clock {
+    pub fn new(sample_rate: f64) -> Self {
+        Self {
+            samples: Arc::new(AtomicU64::new(0)),
+            sample_rate,
+        }
+    }
+
+    pub fn advance(&self, samples: u64) {
+        self.samples.fetch_add(samples, Ordering::Relaxed);
+    }
+
+    /// Time, in seconds, of the next sample to be rendered
+    pub fn time(&self) -> f64 {
+        self.samples.load(Ordering::Relaxed) as f64 / self.sample_rate
+    }
+}
@@ -1,35 +1,41 @@
 use fundsp::hacker::*;
 
-#[derive(Clone, Copy)]
-pub enum Envelope {
-    Ads {
-        attack: f64,
-        decay: f64,
-        sustain: f64,
-    },
-    None,
+use super::automation::Automation;
+
+// Shortest release, a release of 0 would divide by 0
+const MIN_RELEASE: f32 = 0.001;
+
+/// Times in seconds. The release starts when the gate of the note closes, on an
+/// OFF or the next note. A CUT stops the note without a release.
+#[derive(PartialEq, Clone, Copy)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
 }
 
 impl Envelope {
-    pub fn level(&self, time: f64) -> f64 {
-        match self {
-            Envelope::Ads {
-                attack,
-                decay,
-                sustain,
-            } => {
-                if time < *attack {
-                    lerp(0.0_f64, 1.0_f64, time / attack)
-                } else {
-                    let decay_time = time - attack;
-                    if decay_time < *decay {
-                        lerp(1.0_f64, *sustain, decay_time / decay)
-                    } else {
-                        *sustain
-                    }
-                }
-            }
-            Envelope::None => 1.0,
+    pub fn scale_attack(&self, scale: f64) -> Self {
+        Self {
+            attack: self.attack * scale as f32,
+            ..*self
         }
     }
+
+    /// Time the note keeps sounding after its gate closes
+    pub fn release(&self) -> f64 {
+        self.release as f64
+    }
+
+    /// Level of the envelope, from 0 to 1, driven by the `gate` of the note
+    pub fn unit(&self, gate: &Automation) -> Net {
+        gate.unit()
+            >> Net::wrap(Box::new(adsr_live(
+                self.attack,
+                self.decay,
+                self.sustain,
+                self.release.max(MIN_RELEASE),
+            )))
+    }
 }
@@ -0,0 +1,105 @@
+use fundsp::hacker::*;
+
+use super::{automation::Automation, envelope::Envelope};
+
+// Pitch at which key tracking leaves the cutoff unchanged, C-4
+const KEY_TRACKING_CENTER: f32 = 261.63;
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum FilterMode {
+    Lowpass,
+    Highpass,
+    Bandpass,
+    Notch,
+    /// Moog ladder lowpass
+    Moog,
+}
+
+impl FilterMode {
+    pub const ALL: [FilterMode; 5] = [
+        FilterMode::Lowpass,
+        FilterMode::Highpass,
+        FilterMode::Bandpass,
+        FilterMode::Notch,
+        FilterMode::Moog,
+    ];
+
+    pub fn get_string(&self) -> &'static str {
+        match self {
+            FilterMode::Lowpass => "LP",
+            FilterMode::Highpass => "HP",
+            FilterMode::Bandpass => "BP",
+            FilterMode::Notch => "NOTCH",
+            FilterMode::Moog => "MOOG",
+        }
+    }
+}
+
+/// Resonant filter with its own envelope. The cutoff moves by
+/// `envelope_amount` octaves at the top of the envelope and follows the pitch
+/// of the note as much as `key_tracking`.
+#[derive(PartialEq, Clone)]
+pub struct Filter {
+    pub mode: FilterMode,
+    /// From 0 (20 Hz) to 1 (20 kHz)
+    pub cutoff: f32,
+    /// From 0 to 1
+    pub resonance: f32,
+    pub key_tracking: f32,
+    pub envelope: Envelope,
+    pub envelope_amount: f32,
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self {
+            mode: FilterMode::Lowpass,
+            cutoff: 0.5,
+            resonance: 0.2,
+            key_tracking: 0.0,
+            envelope: Envelope {
+                attack: 0.0,
+                decay: 0.3,
+                sustain: 0.0,
+                release: 0.3,
+            },
+            envelope_amount: 2.0,
+        }
+    }
+
+    pub fn cutoff_hz(&self) -> f32 {
+        xerp(20.0, 20000.0, self.cutoff)
+    }
+
+    /// Filters `input`, `cutoff_hz` is the cutoff before the envelope and the key tracking
+    pub fn unit(
+        &self,
+        input: Net,
+        frequency: &Automation,
+        gate: &Automation,
+        cutoff_hz: f32,
+    ) -> Net {
+        let key_tracking = self.key_tracking;
+        let amount = self.envelope_amount;
+        let cutoff = (frequency.unit() | self.envelope.unit(gate))
+            >> Net::wrap(Box::new(map(move |i: &Frame<f32, U2>| {
+                let tracking = (i[0] / KEY_TRACKING_CENTER).powf(key_tracking);
+                (cutoff_hz * tracking * (amount * i[1]).exp2()).clamp(20.0, 20000.0)
+            })));
+
+        let (filter, q): (Box<dyn AudioUnit>, f32) = match self.mode {
+            FilterMode::Lowpass => (Box::new(lowpass()), self.q()),
+            FilterMode::Highpass => (Box::new(highpass()), self.q()),
+            FilterMode::Bandpass => (Box::new(bandpass()), self.q()),
+            FilterMode::Notch => (Box::new(notch()), self.q()),
+            FilterMode::Moog => (Box::new(moog()), self.resonance),
+        };
+
+        (input | cutoff | Net::wrap(Box::new(dc(q)))) >> Net::wrap(filter)
+    }
+
+    // Q of the state variable filters
+    fn q(&self) -> f32 {
+        xerp(0.5, 20.0, self.resonance)
+    }
+}
@@ -0,0 +1,180 @@
+use std::f32::consts::TAU;
+
+use fundsp::hacker::*;
+
+use super::{automation::Automation, envelope::Envelope};
+
+pub const NB_OPERATORS: usize = 4;
+
+// Phase deviation, in cycles, of an operator modulated at full level
+const MODULATION_DEPTH: f32 = 2.0;
+// Phase deviation, in cycles, of an operator fed back at full feedback
+const FEEDBACK_DEPTH: f32 = 0.5;
+
+/// Routing of the operators. Operator 1 is the first one, an operator can only
+/// be modulated by the operators after it.
+pub struct Algorithm {
+    pub name: &'static str,
+    /// Operators modulating each operator, as a bit per operator
+    modulators: [u8; NB_OPERATORS],
+    /// Operators heard, as a bit per operator
+    carriers: u8,
+}
+
+pub const ALGORITHMS: [Algorithm; 8] = [
+    Algorithm {
+        name: "4>3>2>1",
+        modulators: [0b0010, 0b0100, 0b1000, 0],
+        carriers: 0b0001,
+    },
+    Algorithm {
+        name: "3+4>2>1",
+        modulators: [0b0010, 0b1100, 0, 0],
+        carriers: 0b0001,
+    },
+    Algorithm {
+        name: "2+(4>3)>1",
+        modulators: [0b0110, 0, 0b1000, 0],
+        carriers: 0b0001,
+    },
+    Algorithm {
+        name: "(4>2)+3>1",
+        modulators: [0b0110, 0b1000, 0, 0],
+        carriers: 0b0001,
+    },
+    Algorithm {
+        name: "4>3, 2>1",
+        modulators: [0b0010, 0, 0b1000, 0],
+        carriers: 0b0101,
+    },
+    Algorithm {
+        name: "4>1, 2, 3",
+        modulators: [0b1000, 0b1000, 0b1000, 0],
+        carriers: 0b0111,
+    },
+    Algorithm {
+        name: "4>3, 2, 1",
+        modulators: [0, 0, 0b1000, 0],
+        carriers: 0b0111,
+    },
+    Algorithm {
+        name: "1, 2, 3, 4",
+        modulators: [0, 0, 0, 0],
+        carriers: 0b1111,
+    },
+];
+
+/// A sine at `ratio` times the pitch of the note, shaped by its own envelope
+#[derive(PartialEq, Clone)]
+pub struct Operator {
+    pub ratio: f32,
+    pub level: f32,
+    pub envelope: Envelope,
+    pub feedback: f32,
+}
+
+impl Operator {
+    pub const RATIOS: [f32; 17] = [
+        0.5, 1.0, 1.41, 2.0, 3.0, 3.5, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 14.0, 16.0,
+    ];
+    pub const RATIO_NAMES: [&'static str; 17] = [
+        "0.5", "1", "1.41", "2", "3", "3.5", "4", "5", "6", "7", "8", "9", "10", "11", "12", "14",
+        "16",
+    ];
+
+    pub fn new(ratio: f32, level: f32, decay: f32, sustain: f32) -> Self {
+        Self {
+            ratio,
+            level,
+            envelope: Envelope {
+                attack: 0.0,
+                decay,
+                sustain,
+                release: 0.3,
+            },
+            feedback: 0.0,
+        }
+    }
+}
+
+/// Four operators played through one of the `ALGORITHMS`
+pub fn unit(
+    operators: &[Operator; NB_OPERATORS],
+    algorithm: usize,
+    frequency: &Automation,
+    gate: &Automation,
+    attack_scale: f64,
+) -> Net {
+    let envelopes = operators
+        .iter()
+        .map(|operator| operator.envelope.scale_attack(attack_scale).unit(gate))
+        .fold(frequency.unit(), |net, envelope| net | envelope);
+    let voice = Voice {
+        ratios: operators.each_ref().map(|operator| operator.ratio),
+        levels: operators.each_ref().map(|operator| operator.level),
+        feedbacks: operators.each_ref().map(|operator| operator.feedback),
+        algorithm,
+        phases: [0.0; NB_OPERATORS],
+        outputs: [0.0; NB_OPERATORS],
+        previous: [0.0; NB_OPERATORS],
+        sample_duration: 1.0 / DEFAULT_SR as f32,
+    };
+    envelopes >> Net::wrap(Box::new(An(voice)))
+}
+
+// Takes the frequency of the note then the level of the envelope of each operator
+#[derive(Clone)]
+struct Voice {
+    ratios: [f32; NB_OPERATORS],
+    levels: [f32; NB_OPERATORS],
+    feedbacks: [f32; NB_OPERATORS],
+    algorithm: usize,
+    phases: [f32; NB_OPERATORS],
+    outputs: [f32; NB_OPERATORS],
+    // Outputs of the sample before, the feedback is averaged over two samples
+    // so that it does not oscillate
+    previous: [f32; NB_OPERATORS],
+    sample_duration: f32,
+}
+
+impl AudioNode for Voice {
+    const ID: u64 = 0x464d_0004;
+    type Inputs = U5;
+    type Outputs = U1;
+
+    fn reset(&mut self) {
+        self.phases = [0.0; NB_OPERATORS];
+        self.outputs = [0.0; NB_OPERATORS];
+        self.previous = [0.0; NB_OPERATORS];
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_duration = (1.0 / sample_rate) as f32;
+    }
+
+    fn tick(&mut self, input: &Frame<f32, Self::Inputs>) -> Frame<f32, Self::Outputs> {
+        let algorithm = &ALGORITHMS[self.algorithm];
+        let mut output = 0.0;
+        // Modulators come after the operators they modulate, they are computed first
+        (0..NB_OPERATORS).rev().for_each(|i| {
+            let modulation = (0..NB_OPERATORS)
+                .filter(|j| algorithm.modulators[i] & (1 << j) != 0)
+                .map(|j| self.outputs[j])
+                .sum::<f32>()
+                * MODULATION_DEPTH;
+            let feedback =
+                self.feedbacks[i] * FEEDBACK_DEPTH * 0.5 * (self.outputs[i] + self.previous[i]);
+            let value = (TAU * (self.phases[i] + modulation + feedback)).sin()
+                * self.levels[i]
+                * input[i + 1];
+            self.previous[i] = self.outputs[i];
+            self.outputs[i] = value;
+            self.phases[i] =
+                (self.phases[i] + input[0] * self.ratios[i] * self.sample_duration).fract();
+            if algorithm.carriers & (1 << i) != 0 {
+                output += value;
+            }
+        });
+        [output / algorithm.carriers.count_ones() as f32].into()
+    }
+}
@@ -0,0 +1,69 @@
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub enum FxCommand {
+    Arpeggio,
+    SlideUp,
+    SlideDown,
+    Retrigger,
+    Volume,
+    Delay,
+    Kill,
+    Chance,
+    Tempo,
+    TempoRamp,
+    RandomVelocity,
+    RandomTiming,
+}
+
+impl FxCommand {
+    pub const ALL: [FxCommand; 12] = [
+        FxCommand::Arpeggio,
+        FxCommand::SlideUp,
+        FxCommand::SlideDown,
+        FxCommand::Retrigger,
+        FxCommand::Volume,
+        FxCommand::Delay,
+        FxCommand::Kill,
+        FxCommand::Chance,
+        FxCommand::Tempo,
+        FxCommand::TempoRamp,
+        FxCommand::RandomVelocity,
+        FxCommand::RandomTiming,
+    ];
+
+    pub fn get_string(&self) -> &'static str {
+        match self {
+            FxCommand::Arpeggio => "ARP",
+            FxCommand::SlideUp => "PUP",
+            FxCommand::SlideDown => "PDN",
+            FxCommand::Retrigger => "RET",
+            FxCommand::Volume => "VOL",
+            FxCommand::Delay => "DEL",
+            FxCommand::Kill => "KIL",
+            FxCommand::Chance => "CHA",
+            FxCommand::Tempo => "TPO",
+            FxCommand::TempoRamp => "TRP",
+            FxCommand::RandomVelocity => "RVL",
+            FxCommand::RandomTiming => "RTM",
+        }
+    }
+}
+
+/// A command and its hex value, executed on the ticks of a step
+///
+/// - ARP xy: cycles the note, +x and +y semitones, one per tick
+/// - PUP xx: slides the pitch up by xx/16 semitone per tick
+/// - PDN xx: slides the pitch down by xx/16 semitone per tick
+/// - RET xx: retriggers the note every xx ticks
+/// - VOL xx: sets the volume of the note
+/// - DEL xx: delays the step by xx ticks
+/// - KIL xx: cuts the note after xx ticks
+/// - CHA xx: triggers the step with a probability of xx/FF
+/// - TPO xx: sets the tempo to xx BPM
+/// - TRP xx: ramps the tempo toward xx BPM, by 1 BPM per tick
+/// - RVL xx: lowers the velocity by a random amount, up to xx
+/// - RTM xx: delays the step by a random number of ticks, up to xx
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub struct Fx {
+    pub command: FxCommand,
+    pub value: u8,
+}
@@ -0,0 +1,91 @@
+use funutd::Rnd;
+
+use super::{
+    phrase::Phrase,
+    step::{Note, Step, MAX_VELOCITY},
+    tone::Tone,
+};
+
+/// How a generator picks the steps it plays
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Pattern {
+    /// `pulses` spread as evenly as possible over `steps`
+    Euclidean,
+    /// Every `steps`th step
+    EveryNth,
+    /// Each step with a probability of `density`/FF
+    Random,
+}
+
+impl Pattern {
+    pub const ALL: [Pattern; 3] = [Pattern::Euclidean, Pattern::EveryNth, Pattern::Random];
+
+    pub fn get_string(&self) -> &'static str {
+        match self {
+            Pattern::Euclidean => "EUC",
+            Pattern::EveryNth => "NTH",
+            Pattern::Random => "RND",
+        }
+    }
+}
+
+/// Writes a rhythm of one note into a phrase. The patterns repeat over the
+/// length of the phrase and are shifted right by `rotation` steps. The random
+/// pattern is drawn from `seed`, the same settings always write the same phrase.
+pub struct Generator {
+    pub pattern: Pattern,
+    pub pulses: usize,
+    pub steps: usize,
+    pub rotation: usize,
+    pub density: u8,
+    pub note: Note,
+    pub instrument: usize,
+    pub velocity: u8,
+    pub seed: u64,
+}
+
+impl Generator {
+    pub fn new() -> Self {
+        Self {
+            pattern: Pattern::Euclidean,
+            pulses: 3,
+            steps: 8,
+            rotation: 0,
+            density: 0x80,
+            note: Note::Tone(Tone {
+                octave: 4,
+                semitone: 0,
+            }),
+            instrument: 0,
+            velocity: MAX_VELOCITY,
+            seed: 0,
+        }
+    }
+
+    fn is_hit(&self, step_id: usize, rnd: &mut Rnd) -> bool {
+        let steps = self.steps.max(1);
+        let i = (step_id + steps - self.rotation % steps) % steps;
+        match self.pattern {
+            Pattern::Euclidean => (i * self.pulses.min(steps)) % steps < self.pulses.min(steps),
+            Pattern::EveryNth => i == 0,
+            Pattern::Random => rnd.bool(self.density as f64 / 255.0),
+        }
+    }
+
+    /// Plays the note on the hits of the pattern and removes the notes of the
+    /// other steps. FX are kept.
+    pub fn generate(&self, phrase: &mut Phrase) {
+        let mut rnd = Rnd::from_u64(self.seed);
+        (0..phrase.length).for_each(|step_id| {
+            let mut step = phrase.steps[step_id].unwrap_or(Step::new());
+            if self.is_hit(step_id, &mut rnd) {
+                step.note = Some(self.note);
+                step.instrument = self.instrument;
+                step.velocity = self.velocity;
+            } else {
+                step.note = None;
+            }
+            phrase.steps[step_id] = if step.is_empty() { None } else { Some(step) };
+        });
+    }
+}
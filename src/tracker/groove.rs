@@ -0,0 +1,36 @@
+use super::track::TICKS_PER_STEP;
+
+pub const MAX_GROOVE_LENGTH: usize = 16;
+
+/// Number of ticks of each step, cycling from the start of each phrase.
+/// The groove ends at its first empty row.
+#[derive(PartialEq, Clone)]
+pub struct Groove {
+    pub ticks: Vec<Option<usize>>,
+}
+
+impl Groove {
+    pub fn new() -> Self {
+        let mut ticks: Vec<Option<usize>> = std::iter::repeat_with(|| None)
+            .take(MAX_GROOVE_LENGTH)
+            .collect();
+        ticks[0] = Some(TICKS_PER_STEP);
+        Self { ticks }
+    }
+
+    pub fn length(&self) -> usize {
+        self.ticks
+            .iter()
+            .take_while(|ticks| ticks.is_some())
+            .count()
+    }
+
+    pub fn step_ticks(&self, step_id: usize) -> usize {
+        let length = self.length();
+        if length == 0 {
+            TICKS_PER_STEP
+        } else {
+            self.ticks[step_id % length].unwrap().max(1)
+        }
+    }
+}
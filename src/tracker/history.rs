@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+
+use fundsp::hacker::Shared;
+
+use super::{
+    chain::Chain, groove::Groove, instrument::Instrument, new_instrument, phrase::Phrase, Tracker,
+};
+
+const MAX_DEPTH: usize = 64;
+
+// Views create slots just by showing them, an empty slot is the same as a new one
+fn same_slots<T: PartialEq>(a: &[Option<T>], b: &[Option<T>], new: &T) -> bool {
+    a.iter()
+        .zip(b)
+        .all(|(a, b)| a.as_ref().unwrap_or(new) == b.as_ref().unwrap_or(new))
+}
+
+fn levels(tracker: &Tracker) -> Vec<&Shared> {
+    let mut levels: Vec<&Shared> = tracker
+        .tracks
+        .iter()
+        .map(|track| &track.mix_level)
+        .collect();
+    levels.extend([
+        &tracker.reverb_mix_level,
+        &tracker.chorus_mix_level,
+        &tracker.delay_mix_level,
+        &tracker.chorus_to_reverb_level,
+        &tracker.delay_to_reverb_level,
+    ]);
+    levels
+}
+
+fn chorus(tracker: &Tracker) -> [f32; 3] {
+    [
+        tracker.chorus_mod_frequency,
+        tracker.chorus_separation,
+        tracker.chorus_variation,
+    ]
+}
+
+fn delay(tracker: &Tracker) -> [f32; 2] {
+    [tracker.delay_time, tracker.delay_decay]
+}
+
+fn reverb(tracker: &Tracker) -> [f32; 5] {
+    [
+        tracker.reverb_room_size,
+        tracker.reverb_time,
+        tracker.reverb_diffusion,
+        tracker.reverb_modulation_speed,
+        tracker.reverb_filter_frequency,
+    ]
+}
+
+/// Everything the user edits in a song
+struct Snapshot {
+    song: Vec<Vec<Option<usize>>>,
+    chains: Vec<Option<Chain>>,
+    phrases: Vec<Option<Phrase>>,
+    grooves: Vec<Option<Groove>>,
+    instruments: Vec<Option<Instrument>>,
+    levels: Vec<f32>,
+    chorus: [f32; 3],
+    delay: [f32; 2],
+    reverb: [f32; 5],
+    bpm: f32,
+    seed: u64,
+}
+
+impl Snapshot {
+    fn of(tracker: &Tracker) -> Self {
+        Self {
+            song: tracker
+                .tracks
+                .iter()
+                .map(|track| track.chains.clone())
+                .collect(),
+            chains: tracker.chains.clone(),
+            phrases: tracker.phrases.clone(),
+            grooves: tracker.grooves.clone(),
+            instruments: tracker.instruments.clone(),
+            levels: levels(tracker).iter().map(|level| level.value()).collect(),
+            chorus: chorus(tracker),
+            delay: delay(tracker),
+            reverb: reverb(tracker),
+            bpm: tracker.bpm,
+            seed: tracker.seed,
+        }
+    }
+
+    fn matches(&self, tracker: &Tracker) -> bool {
+        self.song
+            .iter()
+            .zip(&tracker.tracks)
+            .all(|(chains, track)| *chains == track.chains)
+            && same_slots(&self.chains, &tracker.chains, &Chain::new())
+            && same_slots(&self.phrases, &tracker.phrases, &Phrase::new())
+            && same_slots(&self.grooves, &tracker.grooves, &Groove::new())
+            && same_slots(&self.instruments, &tracker.instruments, &new_instrument())
+            && self
+                .levels
+                .iter()
+                .zip(levels(tracker))
+                .all(|(value, level)| *value == level.value())
+            && self.chorus == chorus(tracker)
+            && self.delay == delay(tracker)
+            && self.reverb == reverb(tracker)
+            && self.bpm == tracker.bpm
+            && self.seed == tracker.seed
+    }
+
+    fn restore(&self, tracker: &mut Tracker) {
+        self.song
+            .iter()
+            .zip(&mut tracker.tracks)
+            .for_each(|(chains, track)| track.chains.clone_from(chains));
+        tracker.chains.clone_from(&self.chains);
+        tracker.phrases.clone_from(&self.phrases);
+        tracker.grooves.clone_from(&self.grooves);
+        tracker.instruments.clone_from(&self.instruments);
+        self.levels
+            .iter()
+            .zip(levels(tracker))
+            .for_each(|(value, level)| level.set(*value));
+
+        // Effects are only rebuilt when they change, to not interrupt their tails
+        if self.chorus != chorus(tracker) {
+            [
+                tracker.chorus_mod_frequency,
+                tracker.chorus_separation,
+                tracker.chorus_variation,
+            ] = self.chorus;
+            tracker.rebuild_chorus();
+        }
+        if self.delay != delay(tracker) {
+            [tracker.delay_time, tracker.delay_decay] = self.delay;
+            tracker.rebuild_delay();
+        }
+        if self.reverb != reverb(tracker) {
+            [
+                tracker.reverb_room_size,
+                tracker.reverb_time,
+                tracker.reverb_diffusion,
+                tracker.reverb_modulation_speed,
+                tracker.reverb_filter_frequency,
+            ] = self.reverb;
+            tracker.rebuild_reverb();
+        }
+        tracker.set_bpm(self.bpm);
+        tracker.seed = self.seed;
+    }
+}
+
+/// Undo and redo of the edits of the song. The views write straight into the
+/// tracker, the history compares the tracker with its last snapshot to find the
+/// edits.
+pub struct History {
+    undos: VecDeque<Snapshot>,
+    redos: Vec<Snapshot>,
+    current: Snapshot,
+    grouping: bool,
+}
+
+impl History {
+    pub fn new(tracker: &Tracker) -> Self {
+        Self {
+            undos: VecDeque::new(),
+            redos: Vec::new(),
+            current: Snapshot::of(tracker),
+            grouping: false,
+        }
+    }
+
+    /// Records the edits made since the last call as an undo step. While `group`
+    /// is true, the edits are merged into a single step.
+    pub fn record(&mut self, tracker: &Tracker, group: bool) {
+        if !self.current.matches(tracker) {
+            let current = std::mem::replace(&mut self.current, Snapshot::of(tracker));
+            if !self.grouping {
+                self.undos.push_back(current);
+                if self.undos.len() > MAX_DEPTH {
+                    self.undos.pop_front();
+                }
+                self.redos.clear();
+            }
+            self.grouping = group;
+        } else if !group {
+            self.grouping = false;
+        }
+    }
+
+    pub fn undo(&mut self, tracker: &mut Tracker) {
+        if let Some(previous) = self.undos.pop_back() {
+            self.redos
+                .push(std::mem::replace(&mut self.current, previous));
+            self.current.restore(tracker);
+            self.grouping = false;
+        }
+    }
+
+    pub fn redo(&mut self, tracker: &mut Tracker) {
+        if let Some(next) = self.redos.pop() {
+            self.undos
+                .push_back(std::mem::replace(&mut self.current, next));
+            self.current.restore(tracker);
+            self.grouping = false;
+        }
+    }
+}
@@ -1,9 +1,36 @@
 use fundsp::hacker::*;
 
-use super::instrument_type::InstrumentType;
+use super::{automation::Automation, instrument_type::InstrumentType};
 
+/// How much the velocity of a note modulates each target, from 0 to 1
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct VelocityRouting {
+    pub amplitude: f32,
+    pub cutoff: f32,
+    pub attack: f32,
+}
+
+impl VelocityRouting {
+    /// Gain applied to the amplitude of the note
+    pub fn gain(&self, velocity: f32) -> f32 {
+        lerp(1.0, velocity, self.amplitude)
+    }
+
+    /// Cutoff frequency of the filter, soft notes are darker
+    pub fn cutoff_hz(&self, velocity: f32, cutoff: f32) -> f32 {
+        cutoff * xerp(1.0, 0.01, self.cutoff * (1.0 - velocity))
+    }
+
+    /// Factor applied to the attack time, soft notes are slower
+    pub fn attack_scale(&self, velocity: f32) -> f64 {
+        lerp(1.0, 10.0, (self.attack * (1.0 - velocity)) as f64)
+    }
+}
+
+#[derive(PartialEq, Clone)]
 pub struct Instrument {
-    ty: InstrumentType,
+    pub ty: InstrumentType,
+    pub velocity_routing: VelocityRouting,
     dry_level: f32,
     reverb_level: f32,
     chorus_level: f32,
@@ -15,6 +42,11 @@ impl Instrument {
     pub fn new(ty: InstrumentType) -> Self {
         Self {
             ty,
+            velocity_routing: VelocityRouting {
+                amplitude: 1.0,
+                cutoff: 0.0,
+                attack: 0.0,
+            },
             dry_level: 1.0,
             reverb_level: 1.0,
             chorus_level: 0.0,
@@ -23,8 +55,23 @@ impl Instrument {
         }
     }
 
-    pub fn unit(&self, frequency: f32, velocity: f32) -> Box<dyn AudioUnit> {
-        let net = Net::wrap(self.ty.unit(frequency, velocity));
+    /// Time a note keeps sounding after its gate closes
+    pub fn release(&self) -> f64 {
+        self.ty.release()
+    }
+
+    pub fn unit(
+        &self,
+        frequency: &Automation,
+        volume: &Automation,
+        gate: &Automation,
+        velocity: f32,
+    ) -> Box<dyn AudioUnit> {
+        let net = Net::wrap(
+            self.ty
+                .unit(frequency, gate, velocity, &self.velocity_routing),
+        ) * self.velocity_routing.gain(velocity)
+            * volume.unit();
         let net = net >> pan(self.pan);
 
         let net = net
@@ -1,29 +1,162 @@
 use fundsp::hacker::*;
 
-use super::{envelope::Envelope, waveform::Waveform};
+use super::{
+    automation::Automation,
+    envelope::Envelope,
+    filter::Filter,
+    fm::{self, Operator, NB_OPERATORS},
+    instrument::VelocityRouting,
+    oscillator::Oscillator,
+    sampler::Sampler,
+    waveform::Waveform,
+};
 
+#[derive(PartialEq, Clone)]
 pub enum InstrumentType {
     None,
     Simple {
         waveform: Waveform,
         envelope: Envelope,
     },
+    /// Two oscillators and noise through a resonant filter
+    Subtractive {
+        oscillators: [Oscillator; 2],
+        noise: f32,
+        filter: Filter,
+        envelope: Envelope,
+    },
+    /// Four sine operators modulating each other, routed by one of `fm::ALGORITHMS`
+    Fm {
+        operators: [Operator; NB_OPERATORS],
+        algorithm: usize,
+    },
+    Sampler(Sampler),
 }
 
 impl InstrumentType {
-    pub fn unit(&self, frequency: f32, _velocity: f32) -> Box<dyn AudioUnit> {
+    pub const NAMES: [&'static str; 5] = ["---", "SIMPLE", "SUBTRACTIVE", "FM", "SAMPLER"];
+
+    /// Default instrument type of each kind, in the order of `NAMES`
+    pub fn from_kind(kind: usize) -> Self {
+        let envelope = Envelope {
+            attack: 0.01,
+            decay: 0.01,
+            sustain: 0.7,
+            release: 0.2,
+        };
+        match kind {
+            0 => InstrumentType::None,
+            1 => InstrumentType::Simple {
+                waveform: Waveform::Saw,
+                envelope,
+            },
+            2 => InstrumentType::Subtractive {
+                oscillators: [
+                    Oscillator::new(Waveform::Saw),
+                    Oscillator {
+                        detune: 0.1,
+                        ..Oscillator::new(Waveform::Saw)
+                    },
+                ],
+                noise: 0.0,
+                filter: Filter::new(),
+                envelope,
+            },
+            // An electric piano: a tine over a body
+            3 => InstrumentType::Fm {
+                operators: [
+                    Operator::new(1.0, 1.0, 2.0, 0.0),
+                    Operator::new(14.0, 0.2, 0.2, 0.0),
+                    Operator::new(1.0, 1.0, 1.5, 0.2),
+                    Operator::new(1.0, 0.4, 1.0, 0.1),
+                ],
+                algorithm: 4,
+            },
+            _ => InstrumentType::Sampler(Sampler::new()),
+        }
+    }
+
+    pub fn kind(&self) -> usize {
+        match self {
+            InstrumentType::None => 0,
+            InstrumentType::Simple { .. } => 1,
+            InstrumentType::Subtractive { .. } => 2,
+            InstrumentType::Fm { .. } => 3,
+            InstrumentType::Sampler(_) => 4,
+        }
+    }
+
+    /// Time a note keeps sounding after its gate closes
+    pub fn release(&self) -> f64 {
+        match self {
+            InstrumentType::None => 0.0,
+            InstrumentType::Simple { envelope, .. }
+            | InstrumentType::Subtractive { envelope, .. } => envelope.release(),
+            InstrumentType::Fm { operators, .. } => operators
+                .iter()
+                .map(|operator| operator.envelope.release())
+                .fold(0.0, f64::max),
+            InstrumentType::Sampler(sampler) => sampler.release(),
+        }
+    }
+
+    pub fn unit(
+        &self,
+        frequency: &Automation,
+        gate: &Automation,
+        velocity: f32,
+        velocity_routing: &VelocityRouting,
+    ) -> Box<dyn AudioUnit> {
+        let attack_scale = velocity_routing.attack_scale(velocity);
         match self {
             InstrumentType::None => Box::new(zero()),
             InstrumentType::Simple {
                 waveform,
                 envelope: evlp,
             } => {
-                let evlp = *evlp;
-                Box::new(
-                    dc(frequency)
-                        >> (envelope(move |t| evlp.level(t)) * Net::wrap(waveform.unit())),
-                )
+                let evlp = evlp.scale_attack(attack_scale);
+                let net = (frequency.unit() >> Net::wrap(waveform.unit(gate))) * evlp.unit(gate);
+                velocity_lowpass(net, velocity, velocity_routing)
+            }
+            InstrumentType::Subtractive {
+                oscillators,
+                noise: noise_level,
+                filter,
+                envelope: evlp,
+            } => {
+                let evlp = evlp.scale_attack(attack_scale);
+                let mix = oscillators[0].unit(frequency, gate)
+                    + oscillators[1].unit(frequency, gate)
+                    + Net::wrap(Box::new(noise() * *noise_level));
+                let cutoff = velocity_routing.cutoff_hz(velocity, filter.cutoff_hz());
+                let net = filter.unit(mix, frequency, gate, cutoff) * evlp.unit(gate);
+                Box::new(net)
+            }
+            InstrumentType::Fm {
+                operators,
+                algorithm,
+            } => {
+                let net = fm::unit(operators, *algorithm, frequency, gate, attack_scale);
+                velocity_lowpass(net, velocity, velocity_routing)
+            }
+            InstrumentType::Sampler(sampler) => {
+                let net = sampler.unit(frequency, gate, attack_scale);
+                velocity_lowpass(net, velocity, velocity_routing)
             }
         }
     }
 }
+
+// Darkens soft notes of the instruments without a filter of their own
+fn velocity_lowpass(
+    net: Net,
+    velocity: f32,
+    velocity_routing: &VelocityRouting,
+) -> Box<dyn AudioUnit> {
+    if velocity_routing.cutoff > 0.0 {
+        let cutoff = velocity_routing.cutoff_hz(velocity, 20000.0);
+        Box::new(net >> lowpass_hz(cutoff, 0.7))
+    } else {
+        Box::new(net)
+    }
+}
@@ -0,0 +1,28 @@
+// A bar is 4 beats of 24 ticks
+pub const TICKS_PER_BAR: u32 = 96;
+
+/// Boundary at which the queued chains of the live mode start
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Quantization {
+    /// The next bar
+    Bar,
+    /// The end of the phrase playing on the track, or the next bar for a silent track
+    Phrase,
+}
+
+impl Quantization {
+    pub fn get_string(&self) -> &'static str {
+        match self {
+            Quantization::Bar => "Bar",
+            Quantization::Phrase => "Phrase",
+        }
+    }
+}
+
+/// What a track does at the next boundary
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Cue {
+    /// Loops the chain of the given row of its song column
+    Launch(usize),
+    Stop,
+}
@@ -1,33 +1,95 @@
+pub mod automation;
 pub mod chain;
+pub mod clock;
 pub mod envelope;
+pub mod filter;
+pub mod fm;
+pub mod fx;
+pub mod generator;
+pub mod groove;
+pub mod history;
 pub mod instrument;
 pub mod instrument_type;
+pub mod live;
+pub mod oscillator;
 pub mod phrase;
+pub mod sampler;
 pub mod step;
 pub mod tone;
 pub mod track;
 pub mod waveform;
+pub mod wavetable;
 
-use std::time::{Duration, Instant};
+use std::collections::HashSet;
 
-use chain::Chain;
+use automation::{Automation, NB_POINTS};
+use chain::{Chain, ChainEntry};
+use clock::Clock;
 use envelope::Envelope;
 use fundsp::hacker::*;
+use funutd::Rnd;
+use fx::FxCommand;
+use groove::Groove;
 use instrument::Instrument;
 use instrument_type::InstrumentType;
+use live::{Cue, Quantization, TICKS_PER_BAR};
 use phrase::Phrase;
+use sampler::Sample;
+use step::{Note, Step};
 use tone::Tone;
-use track::Track;
+use track::{Source, Track};
 use waveform::Waveform;
 
 pub const NB_TRACKS: usize = 8;
 
+// Fade out applied after the release of a note, for envelopes that do not end at 0
+const RELEASE_FADE: f64 = 0.05;
+// Fade out applied to a note stopped by a CUT, just long enough to avoid a click
+const CUT_FADE: f64 = 0.002;
+// How far ahead of the audio thread ticks are scheduled, it must cover the time
+// between two updates of the UI thread
+const LOOKAHEAD: f64 = 0.1;
+/// Slowest base tempo
+pub const MIN_BPM: usize = 20;
+/// Fastest base tempo. A tick can change the automations of a note, so the ticks
+/// scheduled within the lookahead, plus the one in progress, must not exceed
+/// the points an automation holds.
+pub const MAX_BPM: usize = ((NB_POINTS - 2) as f64 / LOOKAHEAD * 60.0 / 24.0) as usize;
+// Directory, relative to the working directory, of the samples offered to the samplers
+const SAMPLES_DIR: &str = "samples";
+
+// Instrument created in an empty slot
+fn new_instrument() -> Instrument {
+    Instrument::new(InstrumentType::Simple {
+        waveform: Waveform::Saw,
+        envelope: Envelope {
+            attack: 0.01,
+            decay: 0.01,
+            sustain: 0.7,
+            release: 0.2,
+        },
+    })
+}
+
+// Voice of a note played outside of the sequence
+struct Audition {
+    track_id: usize,
+    event_id: EventId,
+    gate: Automation,
+    start: f64,
+    release: f64,
+}
+
 pub struct Tracker {
+    /// Tone of the lowest key of the computer keyboard piano
     pub tone: Tone,
     pub tracks: Vec<Track>,
     pub chains: Vec<Option<Chain>>,
     pub phrases: Vec<Option<Phrase>>,
+    pub grooves: Vec<Option<Groove>>,
     pub instruments: Vec<Option<Instrument>>,
+    /// Samples the samplers can play, loaded at startup
+    pub samples: Vec<Sample>,
     pub reverb_mix_level: Shared,
     pub chorus_mix_level: Shared,
     pub delay_mix_level: Shared,
@@ -54,15 +116,23 @@ pub struct Tracker {
     reverb: Slot,
     chorus: Slot,
     delay: Slot,
-    last_update: Option<Instant>,
-    pub play_time: Duration,
+    pub clock: Clock,
+    // Time, on the clock, of the next tick to schedule
+    tick_time: f64,
+    /// Base tempo, the song starts at it
     pub bpm: f32,
-    pub update_duration: Option<Duration>,
+    /// Tempo currently playing, changed by the tempo FX
+    pub tempo: f32,
+    tempo_target: Option<f32>,
     pub tick_count: u32,
-    pub step_count: u32,
-    pub time_since_last_tick: Duration,
-    pub remaining_ticks_for_next_step: u32,
     pub playing: bool,
+    /// Live mode, the song grid queues chains instead of playing the song
+    pub live: Option<Quantization>,
+    /// Seed of the random FX, playback starts from it so renders are reproducible
+    pub seed: u64,
+    rnd: Rnd,
+    // Note played from the keyboard
+    audition: Option<Audition>,
 }
 
 impl Tracker {
@@ -189,12 +259,13 @@ impl Tracker {
         let mut tracker = Self {
             tone: Tone {
                 octave: 4,
-                semitone: 0,
+                semitone: -9,
             },
             tracks,
             phrases: std::iter::repeat_with(|| None).take(256).collect(),
             chains: std::iter::repeat_with(|| None).take(256).collect(),
-            instruments: Vec::new(),
+            grooves: std::iter::repeat_with(|| None).take(256).collect(),
+            instruments: std::iter::repeat_with(|| None).take(256).collect(),
             reverb_mix_level,
             chorus_mix_level,
             delay_mix_level,
@@ -221,31 +292,33 @@ impl Tracker {
             snoop_chorus1,
             snoop_out0,
             snoop_out1,
-            last_update: None,
-            play_time: Duration::from_secs_f32(0.0),
-            time_since_last_tick: Duration::from_secs_f32(0.0),
+            clock: Clock::new(sample_rate),
+            tick_time: 0.0,
             bpm: 128.0,
-            update_duration: None,
+            tempo: 128.0,
+            tempo_target: None,
             tick_count: 0,
-            step_count: 0,
-            remaining_ticks_for_next_step: 6,
             playing: false,
+            live: None,
+            seed: 0,
+            rnd: Rnd::from_u64(0),
+            audition: None,
+            samples: Sample::load_dir(SAMPLES_DIR),
         };
 
+        (0..NB_TRACKS).for_each(|i| {
+            tracker.tracks[i].chains[0] = Some(i);
+            tracker.get_chain(i).phrases[0] = Some(ChainEntry {
+                phrase: i,
+                transpose: 0,
+            });
+        });
+
         tracker.rebuild_reverb();
         tracker.rebuild_chorus();
         tracker.rebuild_delay();
 
-        tracker
-            .instruments
-            .push(Some(Instrument::new(InstrumentType::Simple {
-                waveform: Waveform::Saw,
-                envelope: Envelope::Ads {
-                    attack: 0.01,
-                    decay: 0.01,
-                    sustain: 0.7,
-                },
-            })));
+        tracker.get_instrument(0);
 
         (tracker, backend)
     }
@@ -295,93 +368,405 @@ impl Tracker {
         );
     }
 
-    pub fn _semi_tone_up(&mut self) {
+    pub fn semi_tone_up(&mut self) {
         self.tone = self.tone.up(1);
     }
 
-    pub fn _semi_tone_down(&mut self) {
+    pub fn semi_tone_down(&mut self) {
         self.tone = self.tone.down(1);
     }
 
-    fn step(&mut self) {
-        self.step_count += 1;
-        if self.step_count == self.song_step_count() as u32 {
-            self.step_count = 0;
+    pub fn octave_up(&mut self) {
+        self.tone = self.tone.up(12);
+    }
+
+    pub fn octave_down(&mut self) {
+        self.tone = self.tone.down(12);
+    }
+
+    /// Plays `tone` on `track_id`, outside of the sequence, until the audition stops
+    pub fn audition(&mut self, track_id: usize, tone: Tone, instrument_id: usize, velocity: f32) {
+        self.stop_audition();
+        if let Some(ref instrument) = self.instruments[instrument_id] {
+            let frequency = Automation::new(tone.get_frequency());
+            let volume = Automation::new(1.0);
+            let gate = Automation::new(1.0);
+            let start = self.clock.time();
+            let event_id = self.tracks[track_id].sequencer.push(
+                start,
+                f64::INFINITY,
+                Fade::Smooth,
+                0.0,
+                0.0,
+                instrument.unit(&frequency, &volume, &gate, velocity),
+            );
+            self.audition = Some(Audition {
+                track_id,
+                event_id,
+                gate,
+                start,
+                release: instrument.release(),
+            });
+        }
+    }
+
+    pub fn stop_audition(&mut self) {
+        if let Some(mut audition) = self.audition.take() {
+            let time = self.clock.time();
+            audition.gate.set(time - audition.start, 0.0);
+            let end_time = time + audition.release + RELEASE_FADE;
+            self.tracks[audition.track_id].sequencer.edit(
+                audition.event_id,
+                end_time,
+                RELEASE_FADE,
+            );
         }
-        self.tracks.iter_mut().for_each(|track| {
-            track.step();
-        });
-        self.play_note();
     }
 
     fn tick(&mut self) {
-        self.remaining_ticks_for_next_step -= 1;
-        if self.remaining_ticks_for_next_step == 0 {
-            self.remaining_ticks_for_next_step = 6;
-            self.step();
+        if let Some(target) = self.tempo_target {
+            self.tempo += (target - self.tempo).clamp(-1.0, 1.0);
+            if self.tempo == target {
+                self.tempo_target = None;
+            }
         }
+        (0..self.tracks.len()).for_each(|i| {
+            self.apply_cue(i);
+            if self.tracks[i].source.is_some() {
+                self.update_track(i);
+            }
+        });
     }
 
+    /// Schedules the ticks that fall within the lookahead. Their events are
+    /// pushed at the exact time of the tick so the UI refresh rate does not
+    /// matter.
     pub fn update(&mut self) {
         // The length of a beat is specified by the bpm
         // beat are divided in 24 ticks
         // normally, a phrase's step is 6 ticks
         // so there is 4 steps in a beat
 
-        let now = Instant::now();
-
         if !self.playing {
-            self.last_update = Some(now);
             return;
         }
 
-        let tps = self.bpm * 24.0 / 60.0;
-        let tick_duration = 1.0 / tps;
-
-        if let Some(last_update) = self.last_update {
-            let update_duration = now.duration_since(last_update);
-            self.update_duration = Some(update_duration);
-            self.time_since_last_tick += update_duration;
-            let ticks = (self.time_since_last_tick.as_secs_f32() * tps) as u32;
-            self.tick_count += ticks;
-            (0..ticks).for_each(|_| {
-                self.tick();
-            });
-            self.time_since_last_tick -= ticks * Duration::from_secs_f32(tick_duration);
+        // A tempo change made by a tick applies from the next one, each tick
+        // is scheduled once at the tempo in effect when the previous one ended.
+        let horizon = self.clock.time() + LOOKAHEAD;
+        while self.tick_time < horizon {
+            self.tick();
+            self.tick_count += 1;
+            self.tick_time += 60.0 / (self.tempo as f64 * 24.0);
         }
-        self.last_update = Some(now);
+    }
 
-        let time_since_last_step =
-            (self.tick_count % 6) as f32 * tick_duration + self.time_since_last_tick.as_secs_f32();
-        let next_step_time = 6.0 * tick_duration - time_since_last_step;
+    fn apply_cue(&mut self, track_id: usize) {
+        let track = &self.tracks[track_id];
+        let Some(cue) = track.cue else {
+            return;
+        };
+        let on_bar = self.tick_count.is_multiple_of(TICKS_PER_BAR);
+        let on_boundary = match self.live {
+            Some(Quantization::Phrase) if track.source.is_some() => track.at_phrase_start(),
+            _ => on_bar,
+        };
+        if !on_boundary {
+            return;
+        }
 
-        (0..self.tracks.len()).for_each(|i| {
-            self.update_track(i, next_step_time);
+        self.tracks[track_id].cue = None;
+        match cue {
+            Cue::Launch(row) if self.tracks[track_id].chains[row].is_some() => {
+                self.tracks[track_id].start(Source::Live, row);
+            }
+            _ => {
+                self.release_note(track_id);
+                self.tracks[track_id].source = None;
+            }
+        }
+    }
+
+    /// Opens the gates of the tracks that can be heard: not muted and, when some
+    /// tracks are soloed, soloed
+    pub fn update_gates(&self) {
+        let solo = self.tracks.iter().any(|track| track.solo);
+        self.tracks.iter().for_each(|track| {
+            let audible = !track.mute && (!solo || track.solo);
+            track.gate.set(if audible { 1.0 } else { 0.0 });
         });
     }
 
-    fn update_track(&mut self, track_id: usize, next_step_time: f32) {}
+    /// Sets the base tempo, and the tempo playing if no tempo FX is ramping it
+    pub fn set_bpm(&mut self, bpm: f32) {
+        if bpm != self.bpm {
+            self.bpm = bpm;
+            if self.tempo_target.is_none() {
+                self.tempo = bpm;
+            }
+        }
+    }
 
-    pub fn play_note(&mut self) {
-        let step_id = self.tracks[0].step_cursor;
-        if self.get_phrase(0).steps[step_id].is_none() {
+    /// Queues `cue` on `track_id` for the next boundary. Starts the transport,
+    /// with every track silent, if it is stopped.
+    pub fn queue(&mut self, track_id: usize, cue: Cue) {
+        if !self.playing {
+            self.stop();
+            self.start();
+        }
+        self.tracks[track_id].cue = Some(cue);
+    }
+
+    fn update_track(&mut self, track_id: usize) {
+        self.play_tick(track_id);
+        let track = &mut self.tracks[track_id];
+        track.tick(&self.chains, &self.phrases, &self.grooves);
+    }
+
+    fn current_step(&self, track_id: usize) -> Option<Step> {
+        let track = &self.tracks[track_id];
+        track
+            .phrase_id(&self.chains)
+            .and_then(|phrase_id| self.phrases[phrase_id].as_ref())
+            .filter(|phrase| track.step_cursor < phrase.length)
+            .and_then(|phrase| phrase.steps[track.step_cursor])
+    }
+
+    fn play_tick(&mut self, track_id: usize) {
+        let step = self.current_step(track_id).unwrap_or(Step::new());
+        let tick = self.tracks[track_id].tick_cursor;
+
+        if tick == 0 {
+            let track = &mut self.tracks[track_id];
+            let chance = step.fx_value(FxCommand::Chance).unwrap_or(0xFF);
+            track.step_skipped = chance < 0xFF && !self.rnd.bool(chance as f64 / 255.0);
+
+            let timing = step.fx_value(FxCommand::RandomTiming).unwrap_or(0);
+            track.step_delay = step.fx_value(FxCommand::Delay).unwrap_or(0) as usize;
+            if timing > 0 {
+                track.step_delay += self.rnd.u32_in(0, timing as u32) as usize;
+            }
+
+            let range = step.fx_value(FxCommand::RandomVelocity).unwrap_or(0);
+            track.velocity_drop = if range > 0 {
+                self.rnd.u32_in(0, range as u32) as u8
+            } else {
+                0
+            };
+        }
+
+        let delay = self.tracks[track_id].step_delay;
+        if self.tracks[track_id].step_skipped || tick < delay {
+            self.update_pitch(track_id, 0);
             return;
         }
+        // Ticks since the (possibly delayed) start of the step
+        let tick = tick - delay;
+
+        if tick == 0 {
+            if let Some(note) = step.note {
+                let track = &self.tracks[track_id];
+                let transpose = track.transpose(&self.chains);
+                let velocity = Step {
+                    velocity: step.velocity.saturating_sub(track.velocity_drop),
+                    ..step
+                }
+                .velocity();
+                self.play_note(
+                    track_id,
+                    note.transpose(transpose),
+                    step.instrument,
+                    velocity,
+                );
+            }
+        }
+
+        let mut arpeggio = 0;
+        step.fx.iter().flatten().for_each(|fx| {
+            let value = fx.value as usize;
+            match fx.command {
+                FxCommand::Arpeggio => {
+                    arpeggio = match tick % 3 {
+                        0 => 0,
+                        1 => value >> 4,
+                        _ => value & 0xF,
+                    };
+                }
+                FxCommand::SlideUp => {
+                    if tick > 0 {
+                        self.tracks[track_id].slide += value as f32 / 16.0;
+                    }
+                }
+                FxCommand::SlideDown => {
+                    if tick > 0 {
+                        self.tracks[track_id].slide -= value as f32 / 16.0;
+                    }
+                }
+                FxCommand::Retrigger => {
+                    if tick > 0 && tick.is_multiple_of(value) {
+                        let track = &self.tracks[track_id];
+                        if let Some(tone) = track.tone {
+                            self.play_note(
+                                track_id,
+                                Note::Tone(tone),
+                                track.instrument,
+                                track.velocity,
+                            );
+                        }
+                    }
+                }
+                FxCommand::Volume => {
+                    if tick == 0 {
+                        let track = &mut self.tracks[track_id];
+                        track
+                            .volume
+                            .set(self.tick_time - track.note_start, value as f32 / 255.0);
+                    }
+                }
+                FxCommand::Kill => {
+                    if tick == value {
+                        self.cut_note(track_id);
+                    }
+                }
+                FxCommand::Tempo => {
+                    if tick == 0 && value > 0 {
+                        self.tempo = value as f32;
+                        self.tempo_target = None;
+                    }
+                }
+                FxCommand::TempoRamp => {
+                    if tick == 0 && value > 0 {
+                        self.tempo_target = Some(value as f32);
+                    }
+                }
+                FxCommand::Delay
+                | FxCommand::Chance
+                | FxCommand::RandomVelocity
+                | FxCommand::RandomTiming => {}
+            }
+        });
 
-        let tone = self.get_phrase(0).steps[step_id].as_ref().unwrap().tone;
+        self.update_pitch(track_id, arpeggio);
+    }
 
-        if let Some(ref instrument) = self.instruments[0] {
-            self.tracks[0].sequencer.push_relative(
-                0.0,
-                0.2,
-                Fade::Smooth,
-                0.0,
-                0.05,
-                instrument.unit(tone.get_frequency(), 1.0),
+    fn update_pitch(&mut self, track_id: usize, arpeggio: usize) {
+        let track = &mut self.tracks[track_id];
+        if let Some(tone) = track.tone {
+            track.frequency.set(
+                self.tick_time - track.note_start,
+                tone.get_frequency() * semitone_ratio(arpeggio as f32 + track.slide),
             );
         }
     }
 
+    pub fn play_note(&mut self, track_id: usize, note: Note, instrument_id: usize, velocity: f32) {
+        match note {
+            Note::Tone(tone) => {
+                self.release_note(track_id);
+                if let Some(ref instrument) = self.instruments[instrument_id] {
+                    let track = &mut self.tracks[track_id];
+                    track.tone = Some(tone);
+                    track.instrument = instrument_id;
+                    track.velocity = velocity;
+                    track.slide = 0.0;
+                    // Fresh controls so that the released note keeps its own
+                    track.frequency = Automation::new(tone.get_frequency());
+                    track.volume = Automation::new(1.0);
+                    track.note_gate = Automation::new(1.0);
+                    track.note_release = instrument.release();
+                    track.note_start = self.tick_time;
+                    track.event_id = Some(track.sequencer.push(
+                        self.tick_time,
+                        f64::INFINITY,
+                        Fade::Smooth,
+                        0.0,
+                        0.0,
+                        instrument.unit(
+                            &track.frequency,
+                            &track.volume,
+                            &track.note_gate,
+                            velocity,
+                        ),
+                    ));
+                }
+            }
+            Note::Off => self.release_note(track_id),
+            Note::Cut => self.cut_note(track_id),
+        }
+    }
+
+    fn release_note(&mut self, track_id: usize) {
+        self.release_note_at(track_id, self.tick_time);
+    }
+
+    /// Closes the gate of the note, it ends with the release of its envelope
+    fn release_note_at(&mut self, track_id: usize, time: f64) {
+        let track = &mut self.tracks[track_id];
+        track.tone = None;
+        if let Some(event_id) = track.event_id.take() {
+            // A note scheduled ahead but not started yet releases as soon as it starts
+            let time = time.max(track.note_start);
+            track.note_gate.set(time - track.note_start, 0.0);
+            let end_time = time + track.note_release + RELEASE_FADE;
+            track.sequencer.edit(event_id, end_time, RELEASE_FADE);
+        }
+    }
+
+    /// Stops the note right away, without its release
+    fn cut_note(&mut self, track_id: usize) {
+        let track = &mut self.tracks[track_id];
+        track.tone = None;
+        if let Some(event_id) = track.event_id.take() {
+            let end_time = self.tick_time.max(track.note_start) + CUT_FADE;
+            track.sequencer.edit(event_id, end_time, CUT_FADE);
+        }
+    }
+
+    /// Plays the song from `row`. Tracks with an empty cell on that row stay silent.
+    pub fn play_song(&mut self, row: usize) {
+        self.stop();
+        self.tracks.iter_mut().for_each(|track| {
+            if track.chains[row].is_some() {
+                track.start(Source::Song, row);
+            }
+        });
+        self.start();
+    }
+
+    /// Loops `chain_id` on `track_id`
+    pub fn play_chain(&mut self, track_id: usize, chain_id: usize) {
+        self.stop();
+        self.tracks[track_id].start(Source::Chain(chain_id), 0);
+        self.start();
+    }
+
+    /// Loops `phrase_id` on `track_id`
+    pub fn play_phrase(&mut self, track_id: usize, phrase_id: usize) {
+        self.stop();
+        self.tracks[track_id].start(Source::Phrase(phrase_id), 0);
+        self.start();
+    }
+
+    // Leaves the audio thread the lookahead to receive the first events
+    fn start(&mut self) {
+        self.playing = true;
+        self.tick_time = self.clock.time() + LOOKAHEAD;
+        self.tick_count = 0;
+        self.tempo = self.bpm;
+        self.tempo_target = None;
+        self.rnd = Rnd::from_u64(self.seed);
+    }
+
+    pub fn stop(&mut self) {
+        self.playing = false;
+        let now = self.clock.time();
+        (0..self.tracks.len()).for_each(|i| {
+            self.release_note_at(i, now);
+            self.tracks[i].source = None;
+            self.tracks[i].cue = None;
+        });
+    }
+
     pub fn get_phrase(&mut self, index: usize) -> &mut Phrase {
         if self.phrases[index].is_none() {
             self.phrases[index] = Some(Phrase::new());
@@ -389,7 +774,92 @@ impl Tracker {
         self.phrases[index].as_mut().unwrap()
     }
 
-    fn song_step_count(&self) -> usize {
-        16
+    // Slots a clone can take: empty and not played by any chain. Slots are only
+    // filled when opened, an empty one may already be played.
+    fn free_phrases(&self) -> Vec<usize> {
+        let used: HashSet<usize> = self
+            .chains
+            .iter()
+            .flatten()
+            .flat_map(|chain| chain.phrases.iter().flatten())
+            .map(|entry| entry.phrase)
+            .collect();
+        (0..self.phrases.len())
+            .filter(|id| self.phrases[*id].is_none() && !used.contains(id))
+            .collect()
+    }
+
+    // Same as the phrases, for the chains of the song
+    fn free_chains(&self) -> Vec<usize> {
+        let used: HashSet<usize> = self
+            .tracks
+            .iter()
+            .flat_map(|track| track.chains.iter().flatten())
+            .copied()
+            .collect();
+        (0..self.chains.len())
+            .filter(|id| self.chains[*id].is_none() && !used.contains(id))
+            .collect()
+    }
+
+    /// Copies `phrase_id` into the first free slot and returns its index
+    pub fn clone_phrase(&mut self, phrase_id: usize) -> Option<usize> {
+        let clone_id = *self.free_phrases().first()?;
+        self.phrases[clone_id] = Some(self.phrases[phrase_id].clone().unwrap_or_else(Phrase::new));
+        Some(clone_id)
+    }
+
+    /// Copies `chain_id` into the first free slot and returns its index. A deep
+    /// clone also copies every phrase the chain plays and points the copy at them.
+    pub fn clone_chain(&mut self, chain_id: usize, deep: bool) -> Option<usize> {
+        let mut chain = self.chains[chain_id].clone().unwrap_or_else(Chain::new);
+        let clone_id = *self.free_chains().first()?;
+
+        if deep {
+            let mut phrase_ids: Vec<usize> = chain
+                .phrases
+                .iter()
+                .flatten()
+                .map(|entry| entry.phrase)
+                .collect();
+            phrase_ids.sort();
+            phrase_ids.dedup();
+            // All or nothing
+            if self.free_phrases().len() < phrase_ids.len() {
+                return None;
+            }
+            let clone_ids: Vec<usize> = phrase_ids
+                .iter()
+                .map(|&phrase_id| self.clone_phrase(phrase_id).unwrap())
+                .collect();
+            chain.phrases.iter_mut().flatten().for_each(|entry| {
+                let i = phrase_ids.binary_search(&entry.phrase).unwrap();
+                entry.phrase = clone_ids[i];
+            });
+        }
+
+        self.chains[clone_id] = Some(chain);
+        Some(clone_id)
+    }
+
+    pub fn get_instrument(&mut self, index: usize) -> &mut Instrument {
+        if self.instruments[index].is_none() {
+            self.instruments[index] = Some(new_instrument());
+        }
+        self.instruments[index].as_mut().unwrap()
+    }
+
+    pub fn get_groove(&mut self, index: usize) -> &mut Groove {
+        if self.grooves[index].is_none() {
+            self.grooves[index] = Some(Groove::new());
+        }
+        self.grooves[index].as_mut().unwrap()
+    }
+
+    pub fn get_chain(&mut self, index: usize) -> &mut Chain {
+        if self.chains[index].is_none() {
+            self.chains[index] = Some(Chain::new());
+        }
+        self.chains[index].as_mut().unwrap()
     }
 }
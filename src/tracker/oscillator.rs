@@ -0,0 +1,26 @@
+use fundsp::hacker::*;
+
+use super::{automation::Automation, waveform::Waveform};
+
+/// A waveform at the pitch of the note, shifted by `detune` semitones
+#[derive(PartialEq, Clone)]
+pub struct Oscillator {
+    pub waveform: Waveform,
+    pub level: f32,
+    pub detune: f32,
+}
+
+impl Oscillator {
+    pub fn new(waveform: Waveform) -> Self {
+        Self {
+            waveform,
+            level: 1.0,
+            detune: 0.0,
+        }
+    }
+
+    pub fn unit(&self, frequency: &Automation, gate: &Automation) -> Net {
+        ((frequency.unit() * semitone_ratio(self.detune)) >> Net::wrap(self.waveform.unit(gate)))
+            * self.level
+    }
+}
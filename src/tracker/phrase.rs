@@ -1,13 +1,22 @@
 use super::step::Step;
 
+pub const MAX_PHRASE_LENGTH: usize = 64;
+
+#[derive(PartialEq, Clone)]
 pub struct Phrase {
     pub steps: Vec<Option<Step>>,
+    pub length: usize,
+    pub groove: usize,
 }
 
 impl Phrase {
     pub fn new() -> Self {
         Self {
-            steps: std::iter::repeat_with(|| None).take(16).collect(),
+            steps: std::iter::repeat_with(|| None)
+                .take(MAX_PHRASE_LENGTH)
+                .collect(),
+            length: 16,
+            groove: 0,
         }
     }
 }
@@ -0,0 +1,217 @@
+use std::{path::Path, sync::Arc};
+
+use fundsp::hacker::*;
+
+use super::{automation::Automation, envelope::Envelope, tone::Tone};
+
+/// A sound loaded from a file. The audio is shared by all the voices playing it.
+#[derive(Clone)]
+pub struct Sample {
+    pub name: String,
+    wave: Arc<Wave>,
+}
+
+impl PartialEq for Sample {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.wave, &other.wave)
+    }
+}
+
+impl Sample {
+    /// Samples of the audio files in `dir`, sorted by name. Files that cannot be
+    /// decoded are skipped.
+    pub fn load_dir(dir: impl AsRef<Path>) -> Vec<Sample> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut samples: Vec<Sample> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let wave = Wave::load(entry.path()).ok()?;
+                Some(Sample {
+                    name: entry.file_name().to_string_lossy().into_owned(),
+                    wave: Arc::new(wave),
+                })
+            })
+            .collect();
+        samples.sort_by(|a, b| a.name.cmp(&b.name));
+        samples
+    }
+
+    /// Frames of the sample, with its channels mixed down to one
+    pub fn mono(&self) -> Vec<f32> {
+        let channels = self.wave.channels();
+        (0..self.wave.len())
+            .map(|i| (0..channels).map(|c| self.wave.at(c, i)).sum::<f32>() / channels as f32)
+            .collect()
+    }
+
+    // Length, in seconds, between two points given as fractions of the sample
+    fn duration(&self, start: f32, end: f32) -> f64 {
+        (end - start).max(0.0) as f64 * self.wave.duration()
+    }
+}
+
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum LoopMode {
+    Off,
+    Forward,
+    PingPong,
+}
+
+impl LoopMode {
+    pub const ALL: [LoopMode; 3] = [LoopMode::Off, LoopMode::Forward, LoopMode::PingPong];
+
+    pub fn get_string(&self) -> &'static str {
+        match self {
+            LoopMode::Off => "OFF",
+            LoopMode::Forward => "FWD",
+            LoopMode::PingPong => "PING",
+        }
+    }
+}
+
+/// Plays a sample between `start` and `end`, given as fractions of its length,
+/// pitched by the note relative to `root`. A one shot sample plays to its end,
+/// whatever the length of the note and the loop mode.
+#[derive(PartialEq, Clone)]
+pub struct Sampler {
+    pub sample: Option<Sample>,
+    pub root: Tone,
+    pub start: f32,
+    pub end: f32,
+    pub loop_mode: LoopMode,
+    pub one_shot: bool,
+    pub envelope: Envelope,
+}
+
+impl Sampler {
+    pub fn new() -> Self {
+        Self {
+            sample: None,
+            root: Tone {
+                octave: 4,
+                semitone: -9,
+            },
+            start: 0.0,
+            end: 1.0,
+            loop_mode: LoopMode::Off,
+            one_shot: false,
+            envelope: Envelope {
+                attack: 0.0,
+                decay: 0.0,
+                sustain: 1.0,
+                release: 0.05,
+            },
+        }
+    }
+
+    /// Time a note keeps sounding after its gate closes. A one shot sample is
+    /// given the time to play to its end, down to two octaves below its root.
+    pub fn release(&self) -> f64 {
+        match &self.sample {
+            Some(sample) if self.one_shot => 4.0 * sample.duration(self.start, self.end),
+            _ => self.envelope.release(),
+        }
+    }
+
+    pub fn unit(&self, frequency: &Automation, gate: &Automation, attack_scale: f64) -> Net {
+        let Some(sample) = &self.sample else {
+            return Net::wrap(Box::new(zero()));
+        };
+        let length = sample.wave.len() as f64;
+        let start = (self.start as f64 * length).floor();
+        let player = Player {
+            wave: sample.wave.clone(),
+            root_frequency: self.root.get_frequency() as f64,
+            start,
+            end: (self.end as f64 * length).min(length - 1.0),
+            loop_mode: if self.one_shot {
+                LoopMode::Off
+            } else {
+                self.loop_mode
+            },
+            position: start,
+            direction: 1.0,
+            sample_duration: 1.0 / DEFAULT_SR,
+        };
+        // A one shot sample ignores the release of the note
+        let open = Automation::new(1.0);
+        let gate = if self.one_shot { &open } else { gate };
+        (frequency.unit() >> Net::wrap(Box::new(An(player))))
+            * self.envelope.scale_attack(attack_scale).unit(gate)
+    }
+}
+
+// Reads the wave at the speed given by the frequency of the note, mixing its
+// channels down to one
+#[derive(Clone)]
+struct Player {
+    wave: Arc<Wave>,
+    root_frequency: f64,
+    start: f64,
+    end: f64,
+    loop_mode: LoopMode,
+    position: f64,
+    direction: f64,
+    sample_duration: f64,
+}
+
+impl Player {
+    // Linear interpolation between the two frames around the position
+    fn value(&self) -> f32 {
+        let index = self.position as usize;
+        let next = Ord::min(index + 1, self.wave.len() - 1);
+        let t = (self.position - index as f64) as f32;
+        (0..self.wave.channels())
+            .map(|channel| lerp(self.wave.at(channel, index), self.wave.at(channel, next), t))
+            .sum::<f32>()
+            / self.wave.channels() as f32
+    }
+}
+
+impl AudioNode for Player {
+    const ID: u64 = 0x5341_4d50;
+    type Inputs = U1;
+    type Outputs = U1;
+
+    fn reset(&mut self) {
+        self.position = self.start;
+        self.direction = 1.0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_duration = 1.0 / sample_rate;
+    }
+
+    fn tick(&mut self, input: &Frame<f32, Self::Inputs>) -> Frame<f32, Self::Outputs> {
+        if self.position < self.start || self.position > self.end {
+            return [0.0].into();
+        }
+        let value = self.value();
+
+        let speed =
+            input[0] as f64 / self.root_frequency * self.wave.sample_rate() * self.sample_duration;
+        self.position += self.direction * speed;
+        // Past the end of a sample that does not loop, the player stays silent
+        let length = self.end - self.start;
+        if length > 0.0 {
+            match self.loop_mode {
+                LoopMode::Forward if self.position > self.end => {
+                    self.position = self.start + (self.position - self.end) % length;
+                }
+                LoopMode::PingPong if self.position > self.end => {
+                    self.position = self.end - (self.position - self.end) % length;
+                    self.direction = -1.0;
+                }
+                LoopMode::PingPong if self.position < self.start => {
+                    self.position = self.start + (self.start - self.position) % length;
+                    self.direction = 1.0;
+                }
+                _ => {}
+            }
+        }
+
+        [value].into()
+    }
+}
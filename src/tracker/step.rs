@@ -1,7 +1,69 @@
-use super::tone::Tone;
+use super::{
+    fx::{Fx, FxCommand},
+    tone::Tone,
+};
 
+pub const NB_FX: usize = 3;
+pub const MAX_VELOCITY: u8 = 0x7F;
+
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
+pub enum Note {
+    Tone(Tone),
+    Off,
+    Cut,
+}
+
+impl Note {
+    pub fn transpose(&self, semitones: i32) -> Note {
+        match self {
+            Note::Tone(tone) if semitones > 0 => Note::Tone(tone.up(semitones as u32)),
+            Note::Tone(tone) if semitones < 0 => Note::Tone(tone.down(-semitones as u32)),
+            _ => *self,
+        }
+    }
+
+    pub fn get_string(&self) -> String {
+        match self {
+            Note::Tone(tone) => tone.get_string(),
+            Note::Off => "OFF".into(),
+            Note::Cut => "CUT".into(),
+        }
+    }
+}
+
+#[derive(PartialEq, Copy, Clone)]
 pub struct Step {
-    pub tone: Tone,
+    pub note: Option<Note>,
     pub instrument: usize,
     pub velocity: u8,
+    pub fx: [Option<Fx>; NB_FX],
+}
+
+impl Step {
+    pub fn new() -> Self {
+        Self {
+            note: None,
+            instrument: 0,
+            velocity: MAX_VELOCITY,
+            fx: [None; NB_FX],
+        }
+    }
+
+    /// Velocity from 0 to 1
+    pub fn velocity(&self) -> f32 {
+        self.velocity.min(MAX_VELOCITY) as f32 / MAX_VELOCITY as f32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.note.is_none() && self.fx.iter().all(|fx| fx.is_none())
+    }
+
+    /// Value of the first FX slot holding `command`
+    pub fn fx_value(&self, command: FxCommand) -> Option<u8> {
+        self.fx
+            .iter()
+            .flatten()
+            .find(|fx| fx.command == command)
+            .map(|fx| fx.value)
+    }
 }
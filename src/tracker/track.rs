@@ -1,16 +1,63 @@
 use fundsp::hacker::*;
 
+use super::{
+    automation::Automation,
+    chain::{Chain, ChainEntry},
+    groove::Groove,
+    live::Cue,
+    phrase::Phrase,
+    tone::Tone,
+};
+
+pub const TICKS_PER_STEP: usize = 6;
+
+/// What a track plays
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Source {
+    /// Its column of the song, from the chain cursor
+    Song,
+    /// A chain, in loop
+    Chain(usize),
+    /// A phrase, in loop
+    Phrase(usize),
+    /// The chain of its column of the song under the chain cursor, in loop
+    Live,
+}
+
 pub struct Track {
     pub chains: Vec<Option<usize>>,
     pub event_id: Option<EventId>,
+    pub frequency: Automation,
+    pub volume: Automation,
+    /// Open while the note is held, its release starts when it closes
+    pub note_gate: Automation,
+    // Time the note keeps sounding once released
+    pub note_release: f64,
+    // Time, on the clock, at which the current note started
+    pub note_start: f64,
+    pub tone: Option<Tone>,
+    pub instrument: usize,
+    pub velocity: f32,
+    pub slide: f32,
+    pub step_skipped: bool,
+    // Humanization of the step, drawn on its first tick
+    pub step_delay: usize,
+    pub velocity_drop: u8,
     pub mix_level: Shared,
+    /// Silences the dry signal and the sends of the track, for mute and solo
+    pub gate: Shared,
+    pub mute: bool,
+    pub solo: bool,
     pub snoop_l: Snoop,
     pub snoop_r: Snoop,
     pub sequencer: Sequencer,
     pub net: Net,
+    pub source: Option<Source>,
+    pub cue: Option<Cue>,
     pub chain_cursor: usize,
     pub phrase_cursor: usize,
     pub step_cursor: usize,
+    pub tick_cursor: usize,
 }
 
 impl Track {
@@ -23,9 +70,11 @@ impl Track {
         let backend = sequencer.backend();
 
         let mix_level = shared(1.0);
+        let gate = shared(1.0);
 
         let mut net = Net::wrap(Box::new(backend));
         net = net
+            >> (multipass::<U8>() * (var(&gate) >> follow(0.005) >> split::<U8>()))
             >> (((multipass::<U2>() * (var(&mix_level) | var(&mix_level)))
                 >> (snoop_l_backend | snoop_r_backend))
                 | multipass::<U6>());
@@ -33,18 +82,152 @@ impl Track {
         Self {
             chains: std::iter::repeat_with(|| None).take(256).collect(),
             event_id: None,
+            frequency: Automation::new(0.0),
+            volume: Automation::new(1.0),
+            note_gate: Automation::new(0.0),
+            note_release: 0.0,
+            note_start: 0.0,
+            tone: None,
+            instrument: 0,
+            velocity: 1.0,
+            slide: 0.0,
+            step_skipped: false,
+            step_delay: 0,
+            velocity_drop: 0,
             mix_level,
+            gate,
+            mute: false,
+            solo: false,
             snoop_l,
             snoop_r,
             sequencer,
             net,
+            source: None,
+            cue: None,
             chain_cursor: 0,
             phrase_cursor: 0,
             step_cursor: 0,
+            tick_cursor: 0,
+        }
+    }
+
+    /// Starts playing `source` from the `chain_cursor` row of the song
+    pub fn start(&mut self, source: Source, chain_cursor: usize) {
+        self.source = Some(source);
+        self.chain_cursor = chain_cursor;
+        self.phrase_cursor = 0;
+        self.step_cursor = 0;
+        self.tick_cursor = 0;
+    }
+
+    /// Index of the chain under the chain cursor
+    pub fn chain_id(&self) -> Option<usize> {
+        match self.source? {
+            Source::Song | Source::Live => self.chains[self.chain_cursor],
+            Source::Chain(chain_id) => Some(chain_id),
+            Source::Phrase(_) => None,
+        }
+    }
+
+    fn chain_entry(&self, chains: &[Option<Chain>]) -> Option<ChainEntry> {
+        chains[self.chain_id()?].as_ref()?.phrases[self.phrase_cursor]
+    }
+
+    /// Index of the phrase under the phrase cursor
+    pub fn phrase_id(&self, chains: &[Option<Chain>]) -> Option<usize> {
+        match self.source? {
+            Source::Phrase(phrase_id) => Some(phrase_id),
+            _ => Some(self.chain_entry(chains)?.phrase),
+        }
+    }
+
+    /// Transposition, in semitones, of the phrase under the phrase cursor
+    pub fn transpose(&self, chains: &[Option<Chain>]) -> i32 {
+        match self.source {
+            Some(Source::Phrase(_)) => 0,
+            _ => self.chain_entry(chains).map_or(0, |entry| entry.transpose),
         }
     }
 
-    pub fn step(&mut self) {
-        self.step_cursor = (self.step_cursor + 1) % 16;
+    fn phrase<'a>(
+        &self,
+        chains: &[Option<Chain>],
+        phrases: &'a [Option<Phrase>],
+    ) -> Option<&'a Phrase> {
+        phrases[self.phrase_id(chains)?].as_ref()
+    }
+
+    /// Whether the next tick starts a phrase
+    pub fn at_phrase_start(&self) -> bool {
+        self.step_cursor == 0 && self.tick_cursor == 0
+    }
+
+    /// Number of ticks of the step under the step cursor, given by the groove of the phrase
+    pub fn step_ticks(
+        &self,
+        chains: &[Option<Chain>],
+        phrases: &[Option<Phrase>],
+        grooves: &[Option<Groove>],
+    ) -> usize {
+        let groove_id = self
+            .phrase(chains, phrases)
+            .map_or(0, |phrase| phrase.groove);
+        grooves[groove_id]
+            .as_ref()
+            .map_or(TICKS_PER_STEP, |groove| groove.step_ticks(self.step_cursor))
+    }
+
+    pub fn tick(
+        &mut self,
+        chains: &[Option<Chain>],
+        phrases: &[Option<Phrase>],
+        grooves: &[Option<Groove>],
+    ) {
+        self.tick_cursor += 1;
+        if self.tick_cursor >= self.step_ticks(chains, phrases, grooves) {
+            self.tick_cursor = 0;
+            self.step(chains, phrases);
+        }
+    }
+
+    fn step(&mut self, chains: &[Option<Chain>], phrases: &[Option<Phrase>]) {
+        let length = self
+            .phrase(chains, phrases)
+            .map_or(16, |phrase| phrase.length);
+        self.step_cursor += 1;
+        if self.step_cursor >= length {
+            self.step_cursor = 0;
+            self.next_phrase(chains);
+        }
+    }
+
+    // A chain ends at its first empty row
+    fn next_phrase(&mut self, chains: &[Option<Chain>]) {
+        self.phrase_cursor += 1;
+        let chain_ended = self
+            .chain_id()
+            .and_then(|chain_id| chains[chain_id].as_ref())
+            .and_then(|chain| chain.phrases.get(self.phrase_cursor).copied().flatten())
+            .is_none();
+        if chain_ended {
+            self.phrase_cursor = 0;
+            if self.source == Some(Source::Song) {
+                self.next_chain();
+            }
+        }
+    }
+
+    // The song ends at the first empty row and loops back to the start
+    fn next_chain(&mut self) {
+        self.chain_cursor += 1;
+        if self
+            .chains
+            .get(self.chain_cursor)
+            .copied()
+            .flatten()
+            .is_none()
+        {
+            self.chain_cursor = 0;
+        }
     }
 }
@@ -1,15 +1,45 @@
 use fundsp::hacker::*;
 
+use super::{automation::Automation, wavetable::Wavetable};
+
+#[derive(PartialEq, Clone)]
 pub enum Waveform {
     Sine,
     Saw,
     Triangle,
     Square,
     Pulse { duty_cycle: f32 },
+    Wavetable(Wavetable),
 }
 
 impl Waveform {
-    pub fn unit(&self) -> Box<dyn AudioUnit> {
+    pub const NAMES: [&'static str; 6] = ["SIN", "SAW", "TRI", "SQR", "PLS", "WTB"];
+
+    /// Default waveform of each kind, in the order of `NAMES`
+    pub fn from_kind(kind: usize) -> Self {
+        match kind {
+            0 => Waveform::Sine,
+            1 => Waveform::Saw,
+            2 => Waveform::Triangle,
+            3 => Waveform::Square,
+            4 => Waveform::Pulse { duty_cycle: 0.25 },
+            _ => Waveform::Wavetable(Wavetable::new()),
+        }
+    }
+
+    pub fn kind(&self) -> usize {
+        match self {
+            Waveform::Sine => 0,
+            Waveform::Saw => 1,
+            Waveform::Triangle => 2,
+            Waveform::Square => 3,
+            Waveform::Pulse { .. } => 4,
+            Waveform::Wavetable(_) => 5,
+        }
+    }
+
+    /// Takes the frequency as input, `gate` drives the envelope of a wavetable
+    pub fn unit(&self, gate: &Automation) -> Box<dyn AudioUnit> {
         match self {
             Waveform::Sine => Box::new(sine()),
             Waveform::Saw => Box::new(saw()),
@@ -18,6 +48,7 @@ impl Waveform {
             Waveform::Pulse { duty_cycle } => {
                 Box::new((multipass::<U1>() | dc(*duty_cycle)) >> pulse())
             }
+            Waveform::Wavetable(wavetable) => wavetable.unit(gate),
         }
     }
 }
@@ -0,0 +1,186 @@
+use std::{f32::consts::TAU, sync::Arc};
+
+use fundsp::hacker::*;
+
+use super::{automation::Automation, envelope::Envelope, sampler::Sample};
+
+// Length of a single cycle in a WAV file of concatenated cycles
+const FRAME_SIZE: usize = 2048;
+
+/// Tables generated at startup, offered before the samples
+pub const BUILTIN_NAMES: [&str; 3] = ["BASIC", "PWM", "HARMONICS"];
+
+/// Single-cycle frames an oscillator morphs through. The frames are shared by
+/// all the voices playing them.
+#[derive(Clone)]
+pub struct Frames {
+    pub name: String,
+    frames: Arc<Vec<Vec<f32>>>,
+}
+
+impl PartialEq for Frames {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.frames, &other.frames)
+    }
+}
+
+impl Frames {
+    /// Built-in table of `BUILTIN_NAMES`
+    pub fn builtin(index: usize) -> Self {
+        let frames = match index {
+            // Sine, triangle, saw then square
+            0 => vec![
+                cycle(|t| (TAU * t).sin()),
+                cycle(|t| 1.0 - 4.0 * (t - 0.5).abs()),
+                cycle(|t| 2.0 * t - 1.0),
+                cycle(|t| if t < 0.5 { 1.0 } else { -1.0 }),
+            ],
+            // Pulses from a square to a narrow pulse
+            1 => (0..8)
+                .map(|i| {
+                    let width = lerp(0.5, 0.05, i as f32 / 7.0);
+                    cycle(move |t| if t < width { 1.0 } else { -1.0 })
+                })
+                .collect(),
+            // Harmonics of a saw added one after the other
+            _ => (1..=16)
+                .map(|n| {
+                    cycle(move |t| {
+                        (1..=n)
+                            .map(|k| (TAU * k as f32 * t).sin() / k as f32)
+                            .sum::<f32>()
+                            * 0.6
+                    })
+                })
+                .collect(),
+        };
+        Self {
+            name: BUILTIN_NAMES[index].to_string(),
+            frames: Arc::new(frames),
+        }
+    }
+
+    /// Frames of a WAV file of concatenated cycles of `FRAME_SIZE` samples. A
+    /// shorter file is taken as a single cycle.
+    pub fn from_sample(sample: &Sample) -> Self {
+        let samples = sample.mono();
+        let frames = if samples.len() < FRAME_SIZE {
+            vec![samples]
+        } else {
+            samples
+                .chunks_exact(FRAME_SIZE)
+                .map(|frame| frame.to_vec())
+                .collect()
+        };
+        Self {
+            name: sample.name.clone(),
+            frames: Arc::new(frames),
+        }
+    }
+}
+
+fn cycle(f: impl Fn(f32) -> f32) -> Vec<f32> {
+    (0..FRAME_SIZE)
+        .map(|i| f(i as f32 / FRAME_SIZE as f32))
+        .collect()
+}
+
+/// Oscillator morphing through `frames`. The position, from 0 for the first
+/// frame to 1 for the last, moves by `envelope_amount` at the top of the
+/// envelope and by `lfo_amount` at the peaks of the LFO.
+#[derive(PartialEq, Clone)]
+pub struct Wavetable {
+    pub frames: Frames,
+    pub position: f32,
+    pub envelope: Envelope,
+    pub envelope_amount: f32,
+    pub lfo_rate: f32,
+    pub lfo_amount: f32,
+}
+
+impl Wavetable {
+    pub fn new() -> Self {
+        Self {
+            frames: Frames::builtin(0),
+            position: 0.0,
+            envelope: Envelope {
+                attack: 0.0,
+                decay: 1.0,
+                sustain: 0.0,
+                release: 0.3,
+            },
+            envelope_amount: 0.0,
+            lfo_rate: 1.0,
+            lfo_amount: 0.0,
+        }
+    }
+
+    /// Takes the frequency as input
+    pub fn unit(&self, gate: &Automation) -> Box<dyn AudioUnit> {
+        let position = self.position;
+        let envelope_amount = self.envelope_amount;
+        let lfo_amount = self.lfo_amount;
+        let modulation = (self.envelope.unit(gate) | Net::wrap(Box::new(sine_hz(self.lfo_rate))))
+            >> Net::wrap(Box::new(map(move |i: &Frame<f32, U2>| {
+                (position + envelope_amount * i[0] + lfo_amount * i[1]).clamp(0.0, 1.0)
+            })));
+        let oscillator = TableOscillator {
+            frames: self.frames.frames.clone(),
+            phase: 0.0,
+            sample_duration: 1.0 / DEFAULT_SR as f32,
+        };
+        Box::new(
+            (Net::wrap(Box::new(multipass::<U1>())) | modulation)
+                >> Net::wrap(Box::new(An(oscillator))),
+        )
+    }
+}
+
+// Takes the frequency then the position in the table
+#[derive(Clone)]
+struct TableOscillator {
+    frames: Arc<Vec<Vec<f32>>>,
+    phase: f32,
+    sample_duration: f32,
+}
+
+impl TableOscillator {
+    // Linear interpolation within a frame
+    fn value(frame: &[f32], phase: f32) -> f32 {
+        let position = phase * frame.len() as f32;
+        let index = position as usize % frame.len();
+        let next = (index + 1) % frame.len();
+        lerp(frame[index], frame[next], position.fract())
+    }
+}
+
+impl AudioNode for TableOscillator {
+    const ID: u64 = 0x5754_424c;
+    type Inputs = U2;
+    type Outputs = U1;
+
+    fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    fn set_sample_rate(&mut self, sample_rate: f64) {
+        self.sample_duration = (1.0 / sample_rate) as f32;
+    }
+
+    fn tick(&mut self, input: &Frame<f32, Self::Inputs>) -> Frame<f32, Self::Outputs> {
+        if self.frames.is_empty() || self.frames[0].is_empty() {
+            return [0.0].into();
+        }
+        // Morphs between the two frames around the position
+        let position = input[1] * (self.frames.len() - 1) as f32;
+        let index = position as usize;
+        let next = Ord::min(index + 1, self.frames.len() - 1);
+        let value = lerp(
+            Self::value(&self.frames[index], self.phase),
+            Self::value(&self.frames[next], self.phase),
+            position.fract(),
+        );
+        self.phase = (self.phase + input[0] * self.sample_duration).rem_euclid(1.0);
+        [value].into()
+    }
+}
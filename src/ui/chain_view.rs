@@ -0,0 +1,133 @@
+use crate::{
+    math::to_hex_str_1,
+    tracker::chain::ChainEntry,
+    ui::{keyboard::InputMessage, message::Message},
+};
+
+use super::{
+    block::block,
+    editable_index::editable_index,
+    editable_transpose::editable_transpose,
+    focus_calculator::{view_process_focus_message, FocusCalculator},
+    frame_context::FrameContext,
+    label::label,
+    state::State,
+};
+use ratatui::prelude::*;
+
+/// Clones `chain_id` into a free slot on Alternate, and its phrases too on
+/// DeepClone. Returns the index of the clone.
+pub fn clone_chain(state: &mut State, chain_id: usize, ctx: &mut FrameContext) -> Option<usize> {
+    let mut clone_id = None;
+    ctx.process_messages(|msg, _msgs| match msg {
+        Message::Input(InputMessage::Alternate) => {
+            clone_id = state.tracker.clone_chain(chain_id, false);
+            true
+        }
+        Message::Input(InputMessage::DeepClone) => {
+            clone_id = state.tracker.clone_chain(chain_id, true);
+            true
+        }
+        _ => false,
+    });
+    clone_id
+}
+
+pub fn chain_view(state: &mut State, focused: bool, area: Rect, ctx: &mut FrameContext) {
+    let inner = block(
+        " Chain ".red().bold(),
+        None as Option<&str>,
+        focused,
+        area,
+        ctx,
+    );
+
+    let mut focus_calculator = FocusCalculator::new(state.chain_focused);
+
+    let (focused, rect) = focus_calculator.add(Rect::new(inner.x + 3, inner.y, 2, 1));
+    let mut value = Some(state.chain_edited);
+    if focused {
+        value = clone_chain(state, state.chain_edited, ctx).or(value);
+    }
+    editable_index(&mut value, 255, focused, rect, ctx);
+    if let Some(chain_id) = value {
+        state.chain_edited = chain_id;
+    }
+
+    let chain_id = state.chain_edited;
+
+    ctx.process_messages(|msg, _msgs| match msg {
+        Message::Input(InputMessage::Play) => {
+            state.tracker.play_chain(state.track_edited, chain_id);
+            true
+        }
+        _ => false,
+    });
+
+    (0..16).for_each(|i| {
+        label(
+            &to_hex_str_1(i),
+            Rect::new(inner.x, inner.y + 1 + i as u16, 2, 1),
+            ctx,
+        );
+
+        let entry = state.tracker.get_chain(chain_id).phrases[i as usize];
+
+        let mut phrase = entry.map(|entry| entry.phrase);
+        let (focused, rect) =
+            focus_calculator.add(Rect::new(inner.x + 3, inner.y + 1 + i as u16, 2, 1));
+        if focused {
+            if let Some(phrase_id) = phrase {
+                ctx.process_messages(|msg, _msgs| match msg {
+                    Message::Input(InputMessage::Alternate) => {
+                        phrase = state.tracker.clone_phrase(phrase_id).or(phrase);
+                        true
+                    }
+                    _ => false,
+                });
+            }
+        }
+        editable_index(&mut phrase, 255, focused, rect, ctx);
+
+        if focused {
+            if let Some(phrase_id) = phrase {
+                state.phrase_edited = phrase_id;
+            }
+        }
+
+        let mut transpose = entry.map_or(0, |entry| entry.transpose);
+        let (focused, rect) =
+            focus_calculator.add(Rect::new(inner.x + 6, inner.y + 1 + i as u16, 3, 1));
+        if entry.is_some() {
+            editable_transpose(&mut transpose, focused, rect, ctx);
+        } else {
+            ctx.add(move |buf| {
+                let mut line = Line::raw("---");
+                if focused {
+                    line = line.style(Style::default().fg(Color::Black).bg(Color::White));
+                }
+                line.render(rect, buf);
+            });
+        }
+
+        state.tracker.get_chain(chain_id).phrases[i as usize] =
+            phrase.map(|phrase| ChainEntry { phrase, transpose });
+    });
+
+    state
+        .tracker
+        .tracks
+        .iter()
+        .filter(|track| track.chain_id() == Some(chain_id))
+        .for_each(|track| {
+            let phrase_cursor = track.phrase_cursor;
+            ctx.add(move |buf| {
+                Line::from(">").render(
+                    Rect::new(inner.x + 2, inner.y + 1 + phrase_cursor as u16, 1, 1),
+                    buf,
+                );
+            });
+        });
+
+    view_process_focus_message(&mut state.chain_focused, &focus_calculator, ctx);
+}
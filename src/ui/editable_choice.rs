@@ -0,0 +1,74 @@
+use ratatui::prelude::*;
+
+use super::{frame_context::FrameContext, keyboard::InputMessage, message::Message};
+
+/// Selects one of `names`, `index` is the position of the selected one
+pub fn editable_choice(
+    index: &mut usize,
+    names: &[&str],
+    focused: bool,
+    area: Rect,
+    ctx: &mut FrameContext,
+) {
+    let mut selected = Some(*index);
+    choice(&mut selected, names, false, focused, area, ctx);
+    *index = selected.unwrap_or(*index);
+}
+
+/// Same as `editable_choice`, clearing leaves nothing selected. Editing from
+/// nothing selects the first name.
+pub fn editable_optional_choice(
+    index: &mut Option<usize>,
+    names: &[&str],
+    focused: bool,
+    area: Rect,
+    ctx: &mut FrameContext,
+) {
+    choice(index, names, true, focused, area, ctx);
+}
+
+fn choice(
+    index: &mut Option<usize>,
+    names: &[&str],
+    clearable: bool,
+    focused: bool,
+    area: Rect,
+    ctx: &mut FrameContext,
+) {
+    let count = names.len() as i32;
+    if focused {
+        ctx.process_messages(|msg, _msgs| {
+            let inc = match msg {
+                Message::Input(InputMessage::EditRight) | Message::Input(InputMessage::EditUp) => 1,
+                Message::Input(InputMessage::EditLeft) | Message::Input(InputMessage::EditDown) => {
+                    -1
+                }
+                Message::Input(InputMessage::Clear) if clearable => {
+                    *index = None;
+                    return true;
+                }
+                _ => return false,
+            };
+            *index = Some(match index {
+                Some(i) => (*i as i32 + inc).rem_euclid(count) as usize,
+                None => 0,
+            });
+            true
+        });
+    }
+
+    let txt = match index {
+        Some(i) => names[*i].to_string(),
+        None => "---".to_string(),
+    };
+
+    ctx.add(move |buf| {
+        let mut line = Line::raw(txt);
+        if focused {
+            line = line.style(Style::default().fg(Color::Black).bg(Color::White));
+        } else {
+            line = line.style(Style::default().fg(Color::White));
+        }
+        line.render(area, buf);
+    })
+}
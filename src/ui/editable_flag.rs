@@ -0,0 +1,45 @@
+use ratatui::prelude::*;
+
+use super::{frame_context::FrameContext, keyboard::InputMessage, message::Message};
+
+/// An on/off switch shown as `txt`, lit when on
+pub fn editable_flag(
+    value: &mut bool,
+    txt: &'static str,
+    color: Color,
+    focused: bool,
+    area: Rect,
+    ctx: &mut FrameContext,
+) {
+    if focused {
+        ctx.process_messages(|msg, _msgs| match msg {
+            Message::Input(
+                InputMessage::EditUp
+                | InputMessage::EditDown
+                | InputMessage::EditLeft
+                | InputMessage::EditRight,
+            ) => {
+                *value = !*value;
+                true
+            }
+            Message::Input(InputMessage::Clear) => {
+                *value = false;
+                true
+            }
+            _ => false,
+        });
+    }
+
+    let value = *value;
+    ctx.add(move |buf| {
+        let mut line = Line::raw(txt);
+        if focused {
+            line = line.style(Style::default().fg(Color::Black).bg(Color::White));
+        } else if value {
+            line = line.style(Style::default().fg(Color::Black).bg(color));
+        } else {
+            line = line.style(Style::default().fg(Color::DarkGray));
+        }
+        line.render(area, buf);
+    })
+}
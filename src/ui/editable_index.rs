@@ -0,0 +1,63 @@
+use ratatui::prelude::*;
+
+use crate::math::to_hex_str_2;
+
+use super::{frame_context::FrameContext, keyboard::InputMessage, message::Message};
+
+fn index_inc(index: &mut Option<usize>, inc: i32, max: usize) {
+    if let Some(i) = index {
+        *index = Some((*i as i32 + inc).clamp(0, max as i32) as usize);
+    } else {
+        *index = Some(0);
+    }
+}
+
+pub fn editable_index(
+    index: &mut Option<usize>,
+    max: usize,
+    focused: bool,
+    area: Rect,
+    ctx: &mut FrameContext,
+) {
+    if focused {
+        ctx.process_messages(|msg, _msgs| match msg {
+            Message::Input(InputMessage::EditRight) => {
+                index_inc(index, 1, max);
+                true
+            }
+            Message::Input(InputMessage::EditLeft) => {
+                index_inc(index, -1, max);
+                true
+            }
+            Message::Input(InputMessage::EditUp) => {
+                index_inc(index, 16, max);
+                true
+            }
+            Message::Input(InputMessage::EditDown) => {
+                index_inc(index, -16, max);
+                true
+            }
+            Message::Input(InputMessage::Clear) => {
+                *index = None;
+                true
+            }
+            _ => false,
+        });
+    }
+
+    let txt = if let Some(index) = index {
+        to_hex_str_2(*index as u8)
+    } else {
+        "--".into()
+    };
+
+    ctx.add(move |buf| {
+        let mut line = Line::raw(txt);
+        if focused {
+            line = line.style(Style::default().fg(Color::Black).bg(Color::White));
+        } else {
+            line = line.style(Style::default().fg(Color::White));
+        }
+        line.render(area, buf);
+    })
+}
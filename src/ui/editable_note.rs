@@ -1,60 +1,72 @@
 use ratatui::prelude::*;
 
-use crate::tracker::tone::Tone;
+use crate::tracker::{step::Note, tone::Tone};
 
 use super::{frame_context::FrameContext, keyboard::InputMessage, message::Message};
 
-fn semitone_up(tone: &mut Option<Tone>, n: u32) {
-    if let Some(t) = tone {
-        *tone = Some(t.up(n));
+const DEFAULT_TONE: Tone = Tone {
+    octave: 4,
+    semitone: 0,
+};
+
+fn semitone_up(note: &mut Option<Note>, n: u32) {
+    if let Some(Note::Tone(t)) = note {
+        *note = Some(Note::Tone(t.up(n)));
     } else {
-        *tone = Some(Tone {
-            octave: 4,
-            semitone: 0,
-        });
+        *note = Some(Note::Tone(DEFAULT_TONE));
     }
 }
 
-fn semitone_down(tone: &mut Option<Tone>, n: u32) {
-    if let Some(t) = tone {
-        *tone = Some(t.down(n));
+fn semitone_down(note: &mut Option<Note>, n: u32) {
+    if let Some(Note::Tone(t)) = note {
+        *note = Some(Note::Tone(t.down(n)));
     } else {
-        *tone = Some(Tone {
-            octave: 4,
-            semitone: 0,
-        });
+        *note = Some(Note::Tone(DEFAULT_TONE));
     }
 }
 
-pub fn editable_note(tone: &mut Option<Tone>, focused: bool, area: Rect, ctx: &mut FrameContext) {
+// Cycles through tone, OFF and CUT
+fn alternate(note: &mut Option<Note>) {
+    *note = match note {
+        Some(Note::Tone(_)) => Some(Note::Off),
+        Some(Note::Off) => Some(Note::Cut),
+        Some(Note::Cut) | None => Some(Note::Tone(DEFAULT_TONE)),
+    };
+}
+
+pub fn editable_note(note: &mut Option<Note>, focused: bool, area: Rect, ctx: &mut FrameContext) {
     if focused {
         ctx.process_messages(|msg, _msgs| match msg {
             Message::Input(InputMessage::EditRight) => {
-                semitone_up(tone, 1);
+                semitone_up(note, 1);
                 true
             }
             Message::Input(InputMessage::EditLeft) => {
-                semitone_down(tone, 1);
+                semitone_down(note, 1);
                 true
             }
             Message::Input(InputMessage::EditUp) => {
-                semitone_up(tone, 12);
+                semitone_up(note, 12);
                 true
             }
             Message::Input(InputMessage::EditDown) => {
-                semitone_down(tone, 12);
+                semitone_down(note, 12);
+                true
+            }
+            Message::Input(InputMessage::Alternate) => {
+                alternate(note);
                 true
             }
             Message::Input(InputMessage::Clear) => {
-                *tone = None;
+                *note = None;
                 true
             }
             _ => false,
         });
     }
 
-    let txt = if let Some(tone) = tone {
-        tone.get_string()
+    let txt = if let Some(note) = note {
+        note.get_string()
     } else {
         "---".into()
     };
@@ -0,0 +1,43 @@
+use ratatui::prelude::*;
+
+use super::{frame_context::FrameContext, keyboard::InputMessage, message::Message};
+
+/// A whole number shown in decimal, edited by 1 or by 10
+pub fn editable_number(
+    value: &mut usize,
+    min: usize,
+    max: usize,
+    focused: bool,
+    area: Rect,
+    ctx: &mut FrameContext,
+) {
+    if focused {
+        ctx.process_messages(|msg, _msgs| {
+            if let Message::Input(input) = msg {
+                let inc: i32 = match input {
+                    InputMessage::EditUp => 10,
+                    InputMessage::EditDown => -10,
+                    InputMessage::EditRight => 1,
+                    InputMessage::EditLeft => -1,
+                    _ => 0,
+                };
+                *value = (*value as i32 + inc).clamp(min as i32, max as i32) as usize;
+                inc != 0
+            } else {
+                false
+            }
+        });
+    }
+
+    let txt = format!("{:>3}", value);
+
+    ctx.add(move |buf| {
+        let mut line = Line::raw(txt);
+        if focused {
+            line = line.style(Style::default().fg(Color::Black).bg(Color::White));
+        } else {
+            line = line.style(Style::default().fg(Color::White));
+        }
+        line.render(area, buf);
+    })
+}
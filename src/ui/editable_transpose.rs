@@ -0,0 +1,40 @@
+use ratatui::prelude::*;
+
+use crate::math::to_hex_str_2;
+
+use super::{frame_context::FrameContext, keyboard::InputMessage, message::Message};
+
+pub fn editable_transpose(value: &mut i32, focused: bool, area: Rect, ctx: &mut FrameContext) {
+    if focused {
+        ctx.process_messages(|msg, _msgs| {
+            if let Message::Input(input) = msg {
+                let inc: i32 = match input {
+                    InputMessage::EditUp => 12,
+                    InputMessage::EditDown => -12,
+                    InputMessage::EditRight => 1,
+                    InputMessage::EditLeft => -1,
+                    InputMessage::Clear => -*value,
+                    _ => 0,
+                };
+
+                *value = (*value + inc).clamp(-127, 127);
+                inc != 0 || *input == InputMessage::Clear
+            } else {
+                false
+            }
+        });
+    }
+
+    let sign = if *value < 0 { "-" } else { "+" };
+    let txt = format!("{}{}", sign, to_hex_str_2(value.unsigned_abs() as u8));
+
+    ctx.add(move |buf| {
+        let mut line = Line::raw(txt);
+        if focused {
+            line = line.style(Style::default().fg(Color::Black).bg(Color::White));
+        } else {
+            line = line.style(Style::default().fg(Color::White));
+        }
+        line.render(area, buf);
+    })
+}
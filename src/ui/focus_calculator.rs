@@ -73,8 +73,21 @@ impl FocusCalculator {
         (self.focused == id, rect)
     }
 
+    pub fn rect(&self, id: usize) -> Option<Rect> {
+        self.rects.get(id).copied()
+    }
+
+    /// Moves a focus left past the last control onto it, for views whose
+    /// controls change, and returns it
+    pub fn clamp_focused(&mut self) -> usize {
+        self.focused = self.focused.min(self.rects.len().saturating_sub(1));
+        self.focused
+    }
+
     pub fn to(&self, direction: Direction) -> Result<usize, ()> {
-        let rect = self.rects[self.focused];
+        let Some(&rect) = self.rects.get(self.focused) else {
+            return Err(());
+        };
 
         let focused_center = Point {
             x: rect.x + rect.width / 2,
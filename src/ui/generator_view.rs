@@ -0,0 +1,119 @@
+use crate::{
+    math::to_hex_str_2,
+    tracker::{generator::Pattern, step::MAX_VELOCITY},
+    ui::{keyboard::InputMessage, message::Message},
+};
+
+use super::{
+    block::block,
+    editable_choice::editable_choice,
+    editable_index::editable_index,
+    editable_note::editable_note,
+    focus_calculator::{view_process_focus_message, FocusCalculator},
+    frame_context::FrameContext,
+    label::label,
+    state::State,
+};
+use ratatui::prelude::*;
+
+pub fn generator_view(state: &mut State, focused: bool, area: Rect, ctx: &mut FrameContext) {
+    let inner = block(
+        " Generator ".red().bold(),
+        None as Option<&str>,
+        focused,
+        area,
+        ctx,
+    );
+
+    let mut focus_calculator = FocusCalculator::new(state.generator_focused);
+    let generator = &mut state.generator;
+    let row = |i: u16| Rect::new(inner.x + 9, inner.y + i, 3, 1);
+
+    label("Pattern", Rect::new(inner.x, inner.y, 9, 1), ctx);
+    let (focused, rect) = focus_calculator.add(row(0));
+    let mut pattern = Pattern::ALL
+        .iter()
+        .position(|p| *p == generator.pattern)
+        .unwrap();
+    let names = Pattern::ALL.map(|pattern| pattern.get_string());
+    editable_choice(&mut pattern, &names, focused, rect, ctx);
+    generator.pattern = Pattern::ALL[pattern];
+
+    [
+        ("Pulses", &mut generator.pulses),
+        ("Steps", &mut generator.steps),
+        ("Rotation", &mut generator.rotation),
+    ]
+    .into_iter()
+    .enumerate()
+    .for_each(|(i, (name, value))| {
+        let i = i as u16 + 1;
+        label(name, Rect::new(inner.x, inner.y + i, 9, 1), ctx);
+        let (focused, rect) = focus_calculator.add(row(i));
+        let mut index = Some(*value);
+        editable_index(&mut index, 64, focused, rect, ctx);
+        *value = index.unwrap_or(0);
+    });
+
+    label("Density", Rect::new(inner.x, inner.y + 4, 9, 1), ctx);
+    let (focused, rect) = focus_calculator.add(row(4));
+    let mut value = Some(generator.density as usize);
+    editable_index(&mut value, 255, focused, rect, ctx);
+    generator.density = value.unwrap_or(0) as u8;
+
+    label("Note", Rect::new(inner.x, inner.y + 5, 9, 1), ctx);
+    let (focused, rect) = focus_calculator.add(row(5));
+    let mut note = Some(generator.note);
+    editable_note(&mut note, focused, rect, ctx);
+    generator.note = note.unwrap_or(generator.note);
+
+    label("Instr", Rect::new(inner.x, inner.y + 6, 9, 1), ctx);
+    let (focused, rect) = focus_calculator.add(row(6));
+    let mut value = Some(generator.instrument);
+    editable_index(&mut value, 255, focused, rect, ctx);
+    generator.instrument = value.unwrap_or(0);
+
+    label("Velocity", Rect::new(inner.x, inner.y + 7, 9, 1), ctx);
+    let (focused, rect) = focus_calculator.add(row(7));
+    let mut value = Some(generator.velocity as usize);
+    editable_index(&mut value, MAX_VELOCITY as usize, focused, rect, ctx);
+    generator.velocity = value.unwrap_or(MAX_VELOCITY as usize) as u8;
+
+    label("Seed", Rect::new(inner.x, inner.y + 8, 9, 1), ctx);
+    let (focused, rect) = focus_calculator.add(row(8));
+    let mut value = Some(generator.seed as usize);
+    editable_index(&mut value, 255, focused, rect, ctx);
+    generator.seed = value.unwrap_or(0) as u64;
+
+    // Edit on the last row writes into the phrase being edited
+    let phrase_id = state.phrase_edited;
+    let (focused, rect) = focus_calculator.add(Rect::new(inner.x, inner.y + 9, 12, 1));
+    if focused {
+        ctx.process_messages(|msg, _msgs| match msg {
+            Message::Input(
+                InputMessage::EditUp
+                | InputMessage::EditDown
+                | InputMessage::EditLeft
+                | InputMessage::EditRight,
+            ) => {
+                state
+                    .generator
+                    .generate(state.tracker.get_phrase(phrase_id));
+                true
+            }
+            _ => false,
+        });
+    }
+    let txt = format!("Write to {}", to_hex_str_2(phrase_id as u8));
+    ctx.add(move |buf| {
+        let mut line = Line::raw(txt);
+        if focused {
+            line = line.style(Style::default().fg(Color::Black).bg(Color::White));
+        } else {
+            line = line.style(Style::default().fg(Color::White));
+        }
+        line.render(rect, buf);
+    });
+
+    view_process_focus_message(&mut state.generator_focused, &focus_calculator, ctx);
+}
@@ -0,0 +1,50 @@
+use crate::{math::to_hex_str_1, tracker::groove::MAX_GROOVE_LENGTH};
+
+use super::{
+    block::block,
+    editable_index::editable_index,
+    focus_calculator::{view_process_focus_message, FocusCalculator},
+    frame_context::FrameContext,
+    label::label,
+    state::State,
+};
+use ratatui::prelude::*;
+
+pub fn groove_view(state: &mut State, focused: bool, area: Rect, ctx: &mut FrameContext) {
+    let inner = block(
+        " Groove ".red().bold(),
+        None as Option<&str>,
+        focused,
+        area,
+        ctx,
+    );
+
+    let mut focus_calculator = FocusCalculator::new(state.groove_focused);
+
+    let (focused, rect) = focus_calculator.add(Rect::new(inner.x + 3, inner.y, 2, 1));
+    let mut value = Some(state.groove_edited);
+    editable_index(&mut value, 255, focused, rect, ctx);
+    if let Some(groove_id) = value {
+        state.groove_edited = groove_id;
+    }
+
+    let groove_id = state.groove_edited;
+
+    (0..MAX_GROOVE_LENGTH).for_each(|i| {
+        label(
+            &to_hex_str_1(i as u8),
+            Rect::new(inner.x, inner.y + 1 + i as u16, 2, 1),
+            ctx,
+        );
+
+        let mut value = state.tracker.get_groove(groove_id).ticks[i];
+
+        let (focused, rect) =
+            focus_calculator.add(Rect::new(inner.x + 3, inner.y + 1 + i as u16, 2, 1));
+        editable_index(&mut value, 255, focused, rect, ctx);
+
+        state.tracker.get_groove(groove_id).ticks[i] = value.map(|ticks| ticks.max(1));
+    });
+
+    view_process_focus_message(&mut state.groove_focused, &focus_calculator, ctx);
+}
@@ -0,0 +1,368 @@
+use ratatui::prelude::*;
+
+use crate::tracker::{
+    envelope::Envelope,
+    filter::{Filter, FilterMode},
+    fm::{self, Operator},
+    instrument_type::InstrumentType,
+    oscillator::Oscillator,
+    sampler::{LoopMode, Sample, Sampler},
+    step::Note,
+    waveform::Waveform,
+    wavetable::{Frames, Wavetable, BUILTIN_NAMES},
+};
+
+use super::{
+    block::block,
+    editable_choice::editable_choice,
+    editable_flag::editable_flag,
+    editable_index::editable_index,
+    editable_note::editable_note,
+    editable_value::editable_value,
+    focus_calculator::{view_process_focus_message, FocusCalculator},
+    frame_context::FrameContext,
+    label::label,
+    state::State,
+    title::title,
+};
+
+// The parameters of an instrument do not fit on screen, they are laid out in a
+// list of rows that scrolls like the phrase view: controls are added at their
+// row in the whole list and only drawn when visible.
+struct Rows {
+    inner: Rect,
+    scroll: usize,
+    row: usize,
+    focus_calculator: FocusCalculator,
+    hidden: FrameContext,
+    // Samples offered to the samplers and the wavetables
+    samples: Vec<Sample>,
+}
+
+impl Rows {
+    fn is_visible(&self, row: usize) -> bool {
+        row >= self.scroll && row < self.scroll + self.inner.height as usize
+    }
+
+    fn screen_y(&self, row: usize) -> u16 {
+        self.inner.y + row.saturating_sub(self.scroll) as u16
+    }
+
+    fn skip(&mut self) {
+        self.row += 1;
+    }
+
+    // Adds a row with a title, a label and a control of `width` at `x`
+    fn add_at(
+        &mut self,
+        txt: &str,
+        name: &str,
+        x: u16,
+        width: u16,
+        ctx: &mut FrameContext,
+    ) -> (bool, Rect) {
+        let row = self.row;
+        self.row += 1;
+        let y = self.screen_y(row);
+        if self.is_visible(row) {
+            title(txt, Rect::new(self.inner.x, y, 8, 1), ctx);
+            label(name, Rect::new(self.inner.x + 8, y, 12, 1), ctx);
+        }
+        let (focused, rect) =
+            self.focus_calculator
+                .add(Rect::new(self.inner.x + x, row as u16, width, 1));
+        (focused, Rect { y, ..rect })
+    }
+
+    fn add(&mut self, txt: &str, name: &str, ctx: &mut FrameContext) -> (bool, Rect) {
+        self.add_at(txt, name, 20, 5, ctx)
+    }
+
+    /// Where the control of the last row draws, nowhere when it is scrolled out
+    fn ctx<'a>(&'a mut self, ctx: &'a mut FrameContext) -> &'a mut FrameContext {
+        if self.is_visible(self.row - 1) {
+            ctx
+        } else {
+            &mut self.hidden
+        }
+    }
+
+    fn value(
+        &mut self,
+        txt: &str,
+        name: &str,
+        value: &mut f32,
+        min: f32,
+        max: f32,
+        ctx: &mut FrameContext,
+    ) {
+        let (focused, rect) = self.add(txt, name, ctx);
+        editable_value(value, min, max, focused, rect, self.ctx(ctx));
+    }
+
+    fn choice(
+        &mut self,
+        txt: &str,
+        name: &str,
+        index: &mut usize,
+        names: &[&str],
+        ctx: &mut FrameContext,
+    ) {
+        let (focused, rect) = self.add(txt, name, ctx);
+        editable_choice(index, names, focused, rect, self.ctx(ctx));
+    }
+
+    fn envelope(&mut self, txt: &str, envelope: &mut Envelope, ctx: &mut FrameContext) {
+        self.value(txt, "Attack", &mut envelope.attack, 0.0, 5.0, ctx);
+        self.value("", "Decay", &mut envelope.decay, 0.0, 5.0, ctx);
+        self.value("", "Sustain", &mut envelope.sustain, 0.0, 1.0, ctx);
+        self.value("", "Release", &mut envelope.release, 0.0, 5.0, ctx);
+    }
+
+    fn waveform(&mut self, txt: &str, waveform: &mut Waveform, ctx: &mut FrameContext) {
+        let mut kind = waveform.kind();
+        self.choice(txt, "Waveform", &mut kind, &Waveform::NAMES, ctx);
+        if kind != waveform.kind() {
+            *waveform = Waveform::from_kind(kind);
+        }
+        match waveform {
+            Waveform::Pulse { duty_cycle } => {
+                self.value("", "Width", duty_cycle, 0.01, 0.99, ctx);
+            }
+            Waveform::Wavetable(wavetable) => self.wavetable(wavetable, ctx),
+            _ => {}
+        }
+    }
+
+    // The built-in tables come before the samples
+    fn wavetable(&mut self, wavetable: &mut Wavetable, ctx: &mut FrameContext) {
+        let samples = self.samples.clone();
+        let names: Vec<&str> = BUILTIN_NAMES
+            .into_iter()
+            .chain(samples.iter().map(|sample| sample.name.as_str()))
+            .collect();
+        let current = names
+            .iter()
+            .position(|name| *name == wavetable.frames.name)
+            .unwrap_or(0);
+        let mut index = current;
+        let (focused, rect) = self.add_at("", "", 8, 17, ctx);
+        editable_choice(&mut index, &names, focused, rect, self.ctx(ctx));
+        if index != current {
+            wavetable.frames = match index.checked_sub(BUILTIN_NAMES.len()) {
+                Some(i) => Frames::from_sample(&samples[i]),
+                None => Frames::builtin(index),
+            };
+        }
+        self.value("", "Position", &mut wavetable.position, 0.0, 1.0, ctx);
+        self.value(
+            "",
+            "Env Amount",
+            &mut wavetable.envelope_amount,
+            -1.0,
+            1.0,
+            ctx,
+        );
+        self.value("", "LFO Rate", &mut wavetable.lfo_rate, 0.0, 20.0, ctx);
+        self.value("", "LFO Amount", &mut wavetable.lfo_amount, 0.0, 1.0, ctx);
+        self.envelope("", &mut wavetable.envelope, ctx);
+    }
+
+    fn oscillator(&mut self, txt: &str, oscillator: &mut Oscillator, ctx: &mut FrameContext) {
+        self.waveform(txt, &mut oscillator.waveform, ctx);
+        self.value("", "Level", &mut oscillator.level, 0.0, 1.0, ctx);
+        self.value("", "Detune", &mut oscillator.detune, -12.0, 12.0, ctx);
+    }
+
+    fn operator(&mut self, txt: &str, operator: &mut Operator, ctx: &mut FrameContext) {
+        let mut ratio = Operator::RATIOS
+            .iter()
+            .position(|ratio| *ratio == operator.ratio)
+            .unwrap_or(1);
+        self.choice(txt, "Ratio", &mut ratio, &Operator::RATIO_NAMES, ctx);
+        operator.ratio = Operator::RATIOS[ratio];
+        self.value("", "Level", &mut operator.level, 0.0, 1.0, ctx);
+        self.value("", "Feedback", &mut operator.feedback, 0.0, 1.0, ctx);
+        self.envelope("", &mut operator.envelope, ctx);
+    }
+
+    fn sampler(&mut self, sampler: &mut Sampler, ctx: &mut FrameContext) {
+        let samples = self.samples.clone();
+        let mut index = samples
+            .iter()
+            .position(|sample| Some(sample) == sampler.sample.as_ref())
+            .map_or(0, |i| i + 1);
+        let names: Vec<&str> = std::iter::once("---")
+            .chain(samples.iter().map(|sample| sample.name.as_str()))
+            .collect();
+        let (focused, rect) = self.add_at("Sample", "", 8, 17, ctx);
+        editable_choice(&mut index, &names, focused, rect, self.ctx(ctx));
+        sampler.sample = index.checked_sub(1).map(|i| samples[i].clone());
+
+        let (focused, rect) = self.add("", "Root", ctx);
+        let mut note = Some(Note::Tone(sampler.root));
+        editable_note(&mut note, focused, rect, self.ctx(ctx));
+        if let Some(Note::Tone(tone)) = note {
+            sampler.root = tone;
+        }
+        self.value("", "Start", &mut sampler.start, 0.0, 1.0, ctx);
+        self.value("", "End", &mut sampler.end, 0.0, 1.0, ctx);
+
+        let mut mode = LoopMode::ALL
+            .iter()
+            .position(|m| *m == sampler.loop_mode)
+            .unwrap();
+        let names = LoopMode::ALL.map(|mode| mode.get_string());
+        self.choice("", "Loop", &mut mode, &names, ctx);
+        sampler.loop_mode = LoopMode::ALL[mode];
+
+        let (focused, rect) = self.add("", "One Shot", ctx);
+        editable_flag(
+            &mut sampler.one_shot,
+            "ON",
+            Color::Green,
+            focused,
+            rect,
+            self.ctx(ctx),
+        );
+        self.skip();
+        self.envelope("Envelope", &mut sampler.envelope, ctx);
+    }
+
+    fn filter(&mut self, filter: &mut Filter, ctx: &mut FrameContext) {
+        let mut mode = FilterMode::ALL
+            .iter()
+            .position(|m| *m == filter.mode)
+            .unwrap();
+        let names = FilterMode::ALL.map(|mode| mode.get_string());
+        self.choice("Filter", "Mode", &mut mode, &names, ctx);
+        filter.mode = FilterMode::ALL[mode];
+        self.value("", "Cutoff", &mut filter.cutoff, 0.0, 1.0, ctx);
+        self.value("", "Resonance", &mut filter.resonance, 0.0, 1.0, ctx);
+        self.value("", "Key Track", &mut filter.key_tracking, 0.0, 1.0, ctx);
+        self.value(
+            "",
+            "Env Amount",
+            &mut filter.envelope_amount,
+            -8.0,
+            8.0,
+            ctx,
+        );
+        self.skip();
+        self.envelope("Flt Env", &mut filter.envelope, ctx);
+    }
+}
+
+pub fn instrument_view(state: &mut State, focused: bool, area: Rect, ctx: &mut FrameContext) {
+    let inner = block(
+        " Instrument ".red().bold(),
+        None as Option<&str>,
+        focused,
+        area,
+        ctx,
+    );
+
+    let mut rows = Rows {
+        inner,
+        scroll: state.instrument_scroll,
+        row: 0,
+        focus_calculator: FocusCalculator::new(state.instrument_focused),
+        hidden: FrameContext::new(),
+        samples: state.tracker.samples.clone(),
+    };
+
+    let (focused, rect) = rows.add("", "Instrument", ctx);
+    let mut value = Some(state.instrument_edited);
+    editable_index(&mut value, 255, focused, rect, rows.ctx(ctx));
+    if let Some(instrument_id) = value {
+        state.instrument_edited = instrument_id;
+    }
+
+    let instrument = state.tracker.get_instrument(state.instrument_edited);
+
+    let (focused, rect) = rows.add_at("Type", "", 8, 12, ctx);
+    let mut kind = instrument.ty.kind();
+    editable_choice(
+        &mut kind,
+        &InstrumentType::NAMES,
+        focused,
+        rect,
+        rows.ctx(ctx),
+    );
+    if kind != instrument.ty.kind() {
+        instrument.ty = InstrumentType::from_kind(kind);
+    }
+
+    rows.skip();
+    let routing = &mut instrument.velocity_routing;
+    rows.value(
+        "Velocity",
+        "Amplitude",
+        &mut routing.amplitude,
+        0.0,
+        1.0,
+        ctx,
+    );
+    rows.value("", "Cutoff", &mut routing.cutoff, 0.0, 1.0, ctx);
+    rows.value("", "Attack", &mut routing.attack, 0.0, 1.0, ctx);
+
+    match &mut instrument.ty {
+        InstrumentType::None => {}
+        InstrumentType::Simple { waveform, envelope } => {
+            rows.skip();
+            rows.waveform("Osc", waveform, ctx);
+            rows.skip();
+            rows.envelope("Envelope", envelope, ctx);
+        }
+        InstrumentType::Subtractive {
+            oscillators,
+            noise,
+            filter,
+            envelope,
+        } => {
+            rows.skip();
+            rows.oscillator("Osc 1", &mut oscillators[0], ctx);
+            rows.skip();
+            rows.oscillator("Osc 2", &mut oscillators[1], ctx);
+            rows.skip();
+            rows.value("Noise", "Level", noise, 0.0, 1.0, ctx);
+            rows.skip();
+            rows.filter(filter, ctx);
+            rows.skip();
+            rows.envelope("Envelope", envelope, ctx);
+        }
+        InstrumentType::Fm {
+            operators,
+            algorithm,
+        } => {
+            rows.skip();
+            let (focused, rect) = rows.add_at("Algo", "", 8, 12, ctx);
+            let names = fm::ALGORITHMS.map(|algorithm| algorithm.name);
+            editable_choice(algorithm, &names, focused, rect, rows.ctx(ctx));
+            operators.iter_mut().enumerate().for_each(|(i, operator)| {
+                rows.skip();
+                rows.operator(&format!("Op {}", i + 1), operator, ctx);
+            });
+        }
+        InstrumentType::Sampler(sampler) => {
+            rows.skip();
+            rows.sampler(sampler, ctx);
+        }
+    }
+
+    // The instrument, its type or its waveforms may have changed and removed controls
+    let mut focus_calculator = rows.focus_calculator;
+    state.instrument_focused = focus_calculator.clamp_focused();
+    view_process_focus_message(&mut state.instrument_focused, &focus_calculator, ctx);
+
+    // Keeps the focused row on screen
+    if let Some(rect) = focus_calculator.rect(state.instrument_focused) {
+        let focused_row = rect.y as usize;
+        let visible_rows = inner.height as usize;
+        if focused_row < state.instrument_scroll {
+            state.instrument_scroll = focused_row;
+        } else if focused_row >= state.instrument_scroll + visible_rows {
+            state.instrument_scroll = focused_row + 1 - visible_rows;
+        }
+    }
+}
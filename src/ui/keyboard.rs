@@ -13,6 +13,18 @@ pub enum Key {
     Play,
     Quit,
     Shift,
+    Undo,
+    Redo,
+    Copy,
+    Cut,
+    Paste,
+    /// Key of the piano, in semitones from its lowest key
+    Note(u8),
+    SemitoneUp,
+    SemitoneDown,
+    OctaveUp,
+    OctaveDown,
+    Record,
 }
 
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
@@ -31,6 +43,26 @@ pub enum InputMessage {
     EditLeft,
     EditRight,
     Clear,
+    Alternate,
+    Stop,
+    PlayMode,
+    DeepClone,
+    Undo,
+    Redo,
+    Copy,
+    Cut,
+    Paste,
+    SelectUp,
+    SelectDown,
+    SelectLeft,
+    SelectRight,
+    NoteOn(u8),
+    NoteOff(u8),
+    SemitoneUp,
+    SemitoneDown,
+    OctaveUp,
+    OctaveDown,
+    Record,
 }
 
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
@@ -49,6 +81,10 @@ impl Keyboard {
             keys: HashSet::new(),
         }
     }
+
+    pub fn is_pressed(&self, key: Key) -> bool {
+        self.keys.contains(&key)
+    }
 }
 
 pub fn process_raw_input(keyboard: &mut Keyboard, ctx: &mut FrameContext) {
@@ -59,10 +95,34 @@ pub fn process_raw_input(keyboard: &mut Keyboard, ctx: &mut FrameContext) {
                     let handled = false;
                     let handled = if keyboard.keys.contains(&Key::Option) {
                         match key {
+                            Key::Edit if keyboard.keys.contains(&Key::Shift) => {
+                                msgs.push(Message::Input(InputMessage::DeepClone));
+                                true
+                            }
                             Key::Edit => {
                                 msgs.push(Message::Input(InputMessage::Clear));
                                 true
                             }
+                            Key::Play => {
+                                msgs.push(Message::Input(InputMessage::Stop));
+                                true
+                            }
+                            Key::Up => {
+                                msgs.push(Message::Input(InputMessage::SelectUp));
+                                true
+                            }
+                            Key::Down => {
+                                msgs.push(Message::Input(InputMessage::SelectDown));
+                                true
+                            }
+                            Key::Left => {
+                                msgs.push(Message::Input(InputMessage::SelectLeft));
+                                true
+                            }
+                            Key::Right => {
+                                msgs.push(Message::Input(InputMessage::SelectRight));
+                                true
+                            }
                             _ => handled,
                         }
                     } else {
@@ -95,6 +155,14 @@ pub fn process_raw_input(keyboard: &mut Keyboard, ctx: &mut FrameContext) {
 
                     let handled = if !handled && keyboard.keys.contains(&Key::Shift) {
                         match key {
+                            Key::Edit => {
+                                msgs.push(Message::Input(InputMessage::Alternate));
+                                true
+                            }
+                            Key::Play => {
+                                msgs.push(Message::Input(InputMessage::PlayMode));
+                                true
+                            }
                             Key::Up => {
                                 msgs.push(Message::Input(InputMessage::ShiftUp));
                                 true
@@ -139,6 +207,50 @@ pub fn process_raw_input(keyboard: &mut Keyboard, ctx: &mut FrameContext) {
                                 msgs.push(Message::Input(InputMessage::Play));
                                 true
                             }
+                            Key::Undo => {
+                                msgs.push(Message::Input(InputMessage::Undo));
+                                true
+                            }
+                            Key::Redo => {
+                                msgs.push(Message::Input(InputMessage::Redo));
+                                true
+                            }
+                            Key::Copy => {
+                                msgs.push(Message::Input(InputMessage::Copy));
+                                true
+                            }
+                            Key::Cut => {
+                                msgs.push(Message::Input(InputMessage::Cut));
+                                true
+                            }
+                            Key::Paste => {
+                                msgs.push(Message::Input(InputMessage::Paste));
+                                true
+                            }
+                            Key::Note(n) => {
+                                msgs.push(Message::Input(InputMessage::NoteOn(*n)));
+                                true
+                            }
+                            Key::SemitoneUp => {
+                                msgs.push(Message::Input(InputMessage::SemitoneUp));
+                                true
+                            }
+                            Key::SemitoneDown => {
+                                msgs.push(Message::Input(InputMessage::SemitoneDown));
+                                true
+                            }
+                            Key::OctaveUp => {
+                                msgs.push(Message::Input(InputMessage::OctaveUp));
+                                true
+                            }
+                            Key::OctaveDown => {
+                                msgs.push(Message::Input(InputMessage::OctaveDown));
+                                true
+                            }
+                            Key::Record => {
+                                msgs.push(Message::Input(InputMessage::Record));
+                                true
+                            }
                             _ => handled,
                         }
                     } else {
@@ -148,6 +260,9 @@ pub fn process_raw_input(keyboard: &mut Keyboard, ctx: &mut FrameContext) {
                     keyboard.keys.insert(*key);
                 }
                 RawInputMessage::Release(key) => {
+                    if let Key::Note(n) = key {
+                        msgs.push(Message::Input(InputMessage::NoteOff(*n)));
+                    }
                     keyboard.keys.remove(key);
                 }
             };
@@ -1,6 +1,7 @@
 use ratatui::prelude::*;
 
 use crate::ui::{
+    editable_flag::editable_flag,
     editable_value::editable_value,
     focus_calculator::{view_process_focus_message, FocusCalculator},
     label::label,
@@ -61,7 +62,16 @@ pub fn mixer_view(state: &mut State, focused: bool, area: Rect, ctx: &mut FrameC
             ctx,
         );
         state.tracker.tracks[i].mix_level.set(value);
+
+        let track = &mut state.tracker.tracks[i];
+        let (focused, rect) =
+            focus_calculator.add(Rect::new(inner.x + 1 + i as u16 * 3, inner.y + 6, 1, 1));
+        editable_flag(&mut track.mute, "M", Color::Red, focused, rect, ctx);
+        let (focused, rect) =
+            focus_calculator.add(Rect::new(inner.x + 2 + i as u16 * 3, inner.y + 6, 1, 1));
+        editable_flag(&mut track.solo, "S", Color::Yellow, focused, rect, ctx);
     }
+    state.tracker.update_gates();
 
     let mut value = state.tracker.chorus_mix_level.value();
     render_track(
@@ -1,17 +1,29 @@
 mod block;
+mod chain_view;
 pub mod console;
+mod editable_choice;
+mod editable_flag;
+mod editable_index;
 mod editable_note;
+mod editable_number;
+mod editable_transpose;
 mod editable_value;
 mod effects_view;
 mod focus_calculator;
 pub mod frame_context;
+mod generator_view;
 mod graph;
+mod groove_view;
+mod instrument_view;
 pub mod keyboard;
 mod label;
 pub mod message;
 mod mixer_view;
+mod phrase_block;
 mod phrase_view;
 pub mod render_app;
+mod song_view;
 pub mod state;
 mod title;
+mod transport_view;
 mod vertical_meter;
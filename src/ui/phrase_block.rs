@@ -0,0 +1,183 @@
+use std::ops::RangeInclusive;
+
+use crate::tracker::{
+    fx::Fx,
+    phrase::Phrase,
+    step::{Step, NB_FX},
+};
+
+// Note, instrument, velocity and FX (command and value) columns
+pub const COLUMNS: usize = 3 + 2 * NB_FX;
+
+const VELOCITY_COLUMN: usize = 2;
+
+// Columns of the same kind hold the same kind of value
+fn kind(column: usize) -> usize {
+    if column < 3 {
+        column
+    } else {
+        3 + (column - 3) % 2
+    }
+}
+
+fn fx_index(column: usize) -> usize {
+    (column - 3) / 2
+}
+
+fn is_fx_value(column: usize) -> bool {
+    column >= 3 && kind(column) == 4
+}
+
+// Copies a cell of `from` to a cell of the same kind of `to`
+fn copy_cell(from: &Step, from_column: usize, to: &mut Step, to_column: usize) {
+    match from_column {
+        0 => to.note = from.note,
+        1 => to.instrument = from.instrument,
+        2 => to.velocity = from.velocity,
+        _ => {
+            let (i, j) = (fx_index(from_column), fx_index(to_column));
+            if is_fx_value(from_column) {
+                if let (Some(from_fx), Some(to_fx)) = (from.fx[i], &mut to.fx[j]) {
+                    to_fx.value = from_fx.value;
+                }
+            } else {
+                let value = to.fx[j].map_or(0, |fx| fx.value);
+                to.fx[j] = from.fx[i].map(|fx| Fx {
+                    command: fx.command,
+                    value,
+                });
+            }
+        }
+    }
+}
+
+/// Cells of a phrase, rows and columns included
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub rows: RangeInclusive<usize>,
+    pub columns: RangeInclusive<usize>,
+}
+
+/// Steps copied from a phrase, only their `columns` are pasted
+pub struct Clipboard {
+    steps: Vec<Step>,
+    columns: RangeInclusive<usize>,
+}
+
+fn step(phrase: &Phrase, row: usize) -> Step {
+    phrase.steps[row].unwrap_or(Step::new())
+}
+
+fn set_step(phrase: &mut Phrase, row: usize, step: Step) {
+    phrase.steps[row] = if step.is_empty() { None } else { Some(step) };
+}
+
+impl Block {
+    /// Block between two cells, given as (row, column)
+    pub fn new(from: (usize, usize), to: (usize, usize)) -> Self {
+        Self {
+            rows: from.0.min(to.0)..=from.0.max(to.0),
+            columns: from.1.min(to.1)..=from.1.max(to.1),
+        }
+    }
+
+    pub fn copy(&self, phrase: &Phrase) -> Clipboard {
+        Clipboard {
+            steps: self.rows.clone().map(|row| step(phrase, row)).collect(),
+            columns: self.columns.clone(),
+        }
+    }
+
+    pub fn delete(&self, phrase: &mut Phrase) {
+        let empty = Step::new();
+        self.rows.clone().for_each(|row| {
+            let mut step = step(phrase, row);
+            self.columns
+                .clone()
+                .for_each(|column| copy_cell(&empty, column, &mut step, column));
+            set_step(phrase, row, step);
+        });
+    }
+
+    /// Transposes the notes of the block, if it covers the note column
+    pub fn transpose(&self, phrase: &mut Phrase, semitones: i32) {
+        if !self.columns.contains(&0) {
+            return;
+        }
+        self.rows.clone().for_each(|row| {
+            let mut step = step(phrase, row);
+            step.note = step.note.map(|note| note.transpose(semitones));
+            set_step(phrase, row, step);
+        });
+    }
+
+    /// Fills the velocity and FX value columns of the block with a ramp from
+    /// the value of its first row to the value of its last row. FX columns
+    /// are only interpolated between two FX, the rows in between get the
+    /// command of the first one.
+    pub fn interpolate(&self, phrase: &mut Phrase) {
+        let (first, last) = (*self.rows.start(), *self.rows.end());
+        if last - first < 2 {
+            return;
+        }
+        let (first_step, last_step) = (step(phrase, first), step(phrase, last));
+
+        self.columns.clone().for_each(|column| {
+            let ends = if column == VELOCITY_COLUMN {
+                Some((first_step.velocity, last_step.velocity, None))
+            } else if is_fx_value(column) {
+                let j = fx_index(column);
+                first_step.fx[j]
+                    .zip(last_step.fx[j])
+                    .map(|(from, to)| (from.value, to.value, Some(from.command)))
+            } else {
+                None
+            };
+            let Some((from, to, command)) = ends else {
+                return;
+            };
+
+            (first + 1..last).for_each(|row| {
+                let t = (row - first) as f32 / (last - first) as f32;
+                let value = (from as f32 + (to as f32 - from as f32) * t).round() as u8;
+                let mut step = step(phrase, row);
+                match command {
+                    None => step.velocity = value,
+                    Some(command) => {
+                        let j = fx_index(column);
+                        let command = step.fx[j].map_or(command, |fx| fx.command);
+                        step.fx[j] = Some(Fx { command, value });
+                    }
+                }
+                set_step(phrase, row, step);
+            });
+        });
+    }
+}
+
+impl Clipboard {
+    /// Pastes from (row, column). The columns move to the cursor column when it
+    /// is of the same kind and there is room for them, else they stay in place.
+    pub fn paste(&self, phrase: &mut Phrase, row: usize, column: usize) {
+        let (start, end) = (*self.columns.start(), *self.columns.end());
+        let offset = if kind(start) == kind(column) && column + end - start < COLUMNS {
+            column as isize - start as isize
+        } else {
+            0
+        };
+
+        let length = phrase.length;
+        self.steps
+            .iter()
+            .enumerate()
+            .take_while(|(i, _)| row + i < length)
+            .for_each(|(i, from)| {
+                let mut to = step(phrase, row + i);
+                self.columns.clone().for_each(|from_column| {
+                    let to_column = (from_column as isize + offset) as usize;
+                    copy_cell(from, from_column, &mut to, to_column);
+                });
+                set_step(phrase, row + i, to);
+            });
+    }
+}
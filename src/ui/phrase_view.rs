@@ -1,79 +1,369 @@
 use crate::{
-    math::to_hex_str_1,
-    tracker::{step::Step, tone::Tone},
+    math::to_hex_str_2,
+    tracker::{
+        fx::{Fx, FxCommand},
+        phrase::MAX_PHRASE_LENGTH,
+        step::{Note, Step, MAX_VELOCITY, NB_FX},
+    },
+    ui::{keyboard::InputMessage, message::Message},
 };
 
 use super::{
     block::block,
+    editable_choice::editable_optional_choice,
+    editable_index::editable_index,
     editable_note::editable_note,
-    focus_calculator::{view_process_focus_message, FocusCalculator},
+    focus_calculator::{view_process_focus_message, Direction as Dir, FocusCalculator},
     frame_context::FrameContext,
     label::label,
+    phrase_block::{Block, COLUMNS},
     state::State,
 };
 use ratatui::prelude::*;
 
-fn get_tone(state: &mut State, phrase_id: usize, step_id: usize) -> Option<Tone> {
-    Some(
-        state.tracker.get_phrase(phrase_id).steps[step_id]
-            .as_ref()?
-            .tone,
-    )
+// Length and groove
+const HEADER_CONTROLS: usize = 2;
+
+// Rects of the controls of a row, in focus order
+fn row_rects(x: u16, y: u16) -> Vec<Rect> {
+    let mut rects = vec![
+        Rect::new(x + 3, y, 3, 1),
+        Rect::new(x + 7, y, 2, 1),
+        Rect::new(x + 10, y, 2, 1),
+    ];
+    (0..NB_FX).for_each(|j| {
+        let fx_x = x + 13 + j as u16 * 6;
+        rects.push(Rect::new(fx_x, y, 3, 1));
+        rects.push(Rect::new(fx_x + 3, y, 2, 1));
+    });
+    rects
 }
 
-fn set_tone(state: &mut State, phrase_id: usize, step_id: usize, value: Option<Tone>) {
-    if let Some(tone) = value {
-        if state.tracker.get_phrase(phrase_id).steps[step_id].is_none() {
-            state.tracker.get_phrase(phrase_id).steps[step_id] = Some(Step {
-                tone,
-                instrument: 0,
-                velocity: 64,
-            })
-        }
-        state.tracker.get_phrase(phrase_id).steps[step_id]
-            .as_mut()
-            .unwrap()
-            .tone = tone;
-    } else {
-        state.tracker.get_phrase(phrase_id).steps[step_id] = None;
-    }
+fn get_step(state: &mut State, phrase_id: usize, step_id: usize) -> Step {
+    state.tracker.get_phrase(phrase_id).steps[step_id].unwrap_or(Step::new())
+}
+
+fn set_step(state: &mut State, phrase_id: usize, step_id: usize, step: Step) {
+    state.tracker.get_phrase(phrase_id).steps[step_id] =
+        if step.is_empty() { None } else { Some(step) };
 }
 
 pub fn phrase_view(state: &mut State, focused: bool, area: Rect, ctx: &mut FrameContext) {
     let inner = block(
-        " Mixer ".red().bold(),
+        " Phrase ".red().bold(),
         None as Option<&str>,
         focused,
         area,
         ctx,
     );
 
+    let phrase_id = state.phrase_edited;
+    let length = state.tracker.get_phrase(phrase_id).length;
+
+    // The focus calculator works on the whole phrase, rows are numbered from the top
+    // of the phrase and only the ones inside the scrolled window are drawn.
+    let last_control = HEADER_CONTROLS + length * COLUMNS - 1;
+    state.phrase_focused = state.phrase_focused.min(last_control);
+    let visible_rows = inner.height.saturating_sub(1) as usize;
+    if state.phrase_focused >= HEADER_CONTROLS {
+        let focused_row = (state.phrase_focused - HEADER_CONTROLS) / COLUMNS;
+        if focused_row < state.phrase_scroll {
+            state.phrase_scroll = focused_row;
+        } else if focused_row >= state.phrase_scroll + visible_rows {
+            state.phrase_scroll = focused_row + 1 - visible_rows;
+        }
+    }
+    state.phrase_scroll = state.phrase_scroll.min(length.saturating_sub(visible_rows));
+    let scroll = state.phrase_scroll;
+    let is_visible = |i: usize| i >= scroll && i < scroll + visible_rows;
+    let screen = |rect: Rect, i: usize| Rect {
+        y: inner.y + 1 + i.saturating_sub(scroll) as u16,
+        ..rect
+    };
+
+    ctx.process_messages(|msg, _msgs| match msg {
+        Message::Input(InputMessage::Play) => {
+            state.tracker.play_phrase(state.track_edited, phrase_id);
+            true
+        }
+        _ => false,
+    });
+
+    // Cell under the cursor, as (row, column), none on the header
+    let cursor = state
+        .phrase_focused
+        .checked_sub(HEADER_CONTROLS)
+        .map(|i| (i / COLUMNS, i % COLUMNS));
+    state.phrase_selection = state
+        .phrase_selection
+        .filter(|_| cursor.is_some())
+        .map(|(row, column)| (row.min(length - 1), column));
+    let selection = state
+        .phrase_selection
+        .zip(cursor)
+        .map(|(anchor, cursor)| Block::new(anchor, cursor));
+
+    // Without a selection, copy, cut and paste work on the cell under the cursor.
+    // With one, edits apply to the whole block.
+    if let Some(cursor) = cursor {
+        let block = selection.clone().unwrap_or(Block::new(cursor, cursor));
+        let selecting = selection.is_some();
+        let clipboard = &mut state.clipboard;
+        let phrase = state.tracker.get_phrase(phrase_id);
+        ctx.process_messages(|msg, _msgs| match msg {
+            Message::Input(InputMessage::Copy) => {
+                *clipboard = Some(block.copy(phrase));
+                true
+            }
+            Message::Input(InputMessage::Cut) => {
+                *clipboard = Some(block.copy(phrase));
+                block.delete(phrase);
+                true
+            }
+            Message::Input(InputMessage::Paste) => {
+                if let Some(clipboard) = clipboard {
+                    clipboard.paste(phrase, cursor.0, cursor.1);
+                }
+                true
+            }
+            Message::Input(InputMessage::Clear) if selecting => {
+                block.delete(phrase);
+                true
+            }
+            Message::Input(InputMessage::EditLeft) if selecting => {
+                block.transpose(phrase, -1);
+                true
+            }
+            Message::Input(InputMessage::EditRight) if selecting => {
+                block.transpose(phrase, 1);
+                true
+            }
+            Message::Input(InputMessage::EditDown) if selecting => {
+                block.transpose(phrase, -12);
+                true
+            }
+            Message::Input(InputMessage::EditUp) if selecting => {
+                block.transpose(phrase, 12);
+                true
+            }
+            Message::Input(InputMessage::Alternate) if selecting => {
+                block.interpolate(phrase);
+                true
+            }
+            _ => false,
+        });
+    }
+
+    // Notes played on the keyboard audition the instrument of the row and, when
+    // recording, are written into it before moving to the next row
+    if let Some((row, _)) = cursor {
+        let mut key = None;
+        ctx.process_messages(|msg, _msgs| match msg {
+            Message::Input(InputMessage::NoteOn(n)) => {
+                key = Some(*n);
+                true
+            }
+            _ => false,
+        });
+        if let Some(key) = key {
+            let tone = state.tracker.tone.up(key as u32);
+            let mut step = get_step(state, phrase_id, row);
+            if step.note.is_none() {
+                step.instrument = state.instrument_edited;
+            }
+            state
+                .tracker
+                .audition(state.track_edited, tone, step.instrument, 1.0);
+            state.audition_key = Some(key);
+            if state.recording {
+                step.note = Some(Note::Tone(tone));
+                set_step(state, phrase_id, row, step);
+                if row + 1 < length {
+                    state.phrase_focused += COLUMNS;
+                }
+            }
+        }
+    }
+
+    // Drawn over the cells, the cursor keeps its own style
+    if let Some(block) = &selection {
+        let rects: Vec<Rect> = block
+            .rows
+            .clone()
+            .filter(|&i| is_visible(i))
+            .flat_map(|i| {
+                let rects = row_rects(inner.x, 0);
+                block
+                    .columns
+                    .clone()
+                    .filter(move |&j| Some((i, j)) != cursor)
+                    .map(move |j| screen(rects[j], i))
+            })
+            .collect();
+        ctx.add(move |buf| {
+            rects
+                .into_iter()
+                .for_each(|rect| buf.set_style(rect, Style::default().bg(Color::DarkGray)));
+        });
+    }
+
     let mut focus_calculator = FocusCalculator::new(state.phrase_focused);
 
-    (0..16).for_each(|i| {
+    label(
+        &to_hex_str_2(phrase_id as u8),
+        Rect::new(inner.x + 3, inner.y, 3, 1),
+        ctx,
+    );
+
+    label("Len", Rect::new(inner.x + 7, inner.y, 4, 1), ctx);
+    let (focused, _) = focus_calculator.add(Rect::new(inner.x + 11, 0, 2, 1));
+    let mut value = Some(length);
+    editable_index(
+        &mut value,
+        MAX_PHRASE_LENGTH,
+        focused,
+        Rect::new(inner.x + 11, inner.y, 2, 1),
+        ctx,
+    );
+    if let Some(length) = value {
+        state.tracker.get_phrase(phrase_id).length = length.max(1);
+    }
+
+    label("Grv", Rect::new(inner.x + 14, inner.y, 4, 1), ctx);
+    let (focused, _) = focus_calculator.add(Rect::new(inner.x + 18, 0, 2, 1));
+    let mut value = Some(state.tracker.get_phrase(phrase_id).groove);
+    editable_index(
+        &mut value,
+        255,
+        focused,
+        Rect::new(inner.x + 18, inner.y, 2, 1),
+        ctx,
+    );
+    if let Some(groove) = value {
+        state.tracker.get_phrase(phrase_id).groove = groove;
+    }
+
+    (0..length).for_each(|i| {
+        let rects = row_rects(inner.x, 1 + i as u16);
+
+        if !is_visible(i) {
+            rects.into_iter().for_each(|rect| {
+                focus_calculator.add(rect);
+            });
+            return;
+        }
+
         label(
-            &to_hex_str_1(i),
-            Rect::new(inner.x, inner.y + i as u16, 2, 1),
+            &to_hex_str_2(i as u8),
+            screen(Rect::new(inner.x, 0, 2, 1), i),
+            ctx,
+        );
+
+        let mut step = get_step(state, phrase_id, i);
+
+        let (focused, rect) = focus_calculator.add(rects[0]);
+        let had_note = step.note.is_some();
+        editable_note(&mut step.note, focused, screen(rect, i), ctx);
+        // New notes repeat the last instrument used
+        if !had_note && step.note.is_some() {
+            step.instrument = state.instrument_edited;
+        }
+
+        let mut instrument = step.note.map(|_| step.instrument);
+        let (focused, rect) = focus_calculator.add(rects[1]);
+        editable_index(&mut instrument, 255, focused, screen(rect, i), ctx);
+        if let Some(instrument) = instrument {
+            step.instrument = instrument;
+            if focused {
+                state.instrument_edited = instrument;
+            }
+        }
+
+        let mut velocity = step.note.map(|_| step.velocity as usize);
+        let (focused, rect) = focus_calculator.add(rects[2]);
+        editable_index(
+            &mut velocity,
+            MAX_VELOCITY as usize,
+            focused,
+            screen(rect, i),
             ctx,
         );
+        step.velocity = velocity.unwrap_or(MAX_VELOCITY as usize) as u8;
+
+        (0..NB_FX).for_each(|j| {
+            let mut command = step.fx[j].map(|fx| {
+                FxCommand::ALL
+                    .iter()
+                    .position(|c| *c == fx.command)
+                    .unwrap()
+            });
+            let (focused, rect) = focus_calculator.add(rects[3 + 2 * j]);
+            let names = FxCommand::ALL.map(|command| command.get_string());
+            editable_optional_choice(&mut command, &names, focused, screen(rect, i), ctx);
 
-        let mut value = get_tone(state, 0, i as usize);
+            let mut value = step.fx[j].map(|fx| fx.value as usize);
+            let (focused, rect) = focus_calculator.add(rects[4 + 2 * j]);
+            editable_index(&mut value, 255, focused, screen(rect, i), ctx);
 
-        let (focused, rect) =
-            focus_calculator.add(Rect::new(inner.x + 2, inner.y + i as u16, 3, 1));
-        editable_note(&mut value, focused, rect, ctx);
+            step.fx[j] = command.map(|command| Fx {
+                command: FxCommand::ALL[command],
+                value: value.unwrap_or(0) as u8,
+            });
+        });
 
-        set_tone(state, 0, i as usize, value);
+        set_step(state, phrase_id, i, step);
     });
 
-    let step_cursor = state.tracker.tracks[0].step_cursor;
+    let chains = &state.tracker.chains;
+    state
+        .tracker
+        .tracks
+        .iter()
+        .filter(|track| track.phrase_id(chains) == Some(phrase_id))
+        .filter(|track| is_visible(track.step_cursor))
+        .for_each(|track| {
+            let rect = screen(Rect::new(inner.x + 2, 0, 1, 1), track.step_cursor);
+            ctx.add(move |buf| {
+                Line::from(">").render(rect, buf);
+            });
+        });
 
-    ctx.add(move |buf| {
-        Line::from(">").render(
-            Rect::new(inner.x + 1, inner.y + step_cursor as u16, 1, 1),
-            buf,
-        );
+    // Option and arrows select cells from the cursor, a move without it ends
+    // the selection
+    let mut direction = Dir::None;
+    ctx.process_messages(|msg, _msgs| match msg {
+        Message::Input(InputMessage::SelectUp) => {
+            direction = Dir::Up;
+            true
+        }
+        Message::Input(InputMessage::SelectDown) => {
+            direction = Dir::Down;
+            true
+        }
+        Message::Input(InputMessage::SelectLeft) => {
+            direction = Dir::Left;
+            true
+        }
+        Message::Input(InputMessage::SelectRight) => {
+            direction = Dir::Right;
+            true
+        }
+        Message::Input(
+            InputMessage::Up | InputMessage::Down | InputMessage::Left | InputMessage::Right,
+        ) => {
+            state.phrase_selection = None;
+            false
+        }
+        _ => false,
     });
+    if let Some(cursor) = cursor {
+        if let Ok(focus_id) = focus_calculator.to(direction) {
+            if focus_id >= HEADER_CONTROLS {
+                state.phrase_selection.get_or_insert(cursor);
+                state.phrase_focused = focus_id;
+            }
+        }
+    }
 
     view_process_focus_message(&mut state.phrase_focused, &focus_calculator, ctx);
 }
@@ -2,17 +2,24 @@ use ratatui::prelude::*;
 
 use super::{
     block::block,
+    chain_view::chain_view,
     console::{console, console_log},
     effects_view::effects_view,
     focus_calculator::{Direction as Dir, FocusCalculator},
     frame_context::FrameContext,
+    generator_view::generator_view,
     graph::graph,
-    keyboard::{process_raw_input, InputMessage},
+    groove_view::groove_view,
+    instrument_view::instrument_view,
+    keyboard::{process_raw_input, InputMessage, Key},
     message::Message,
     mixer_view::mixer_view,
     phrase_view::phrase_view,
+    song_view::song_view,
     state::State,
+    transport_view::transport_view,
 };
+use crate::tracker::{live::Quantization, NB_TRACKS};
 
 fn render_view(
     view: fn(&mut State, bool, Rect, &mut FrameContext),
@@ -42,11 +49,62 @@ pub fn render_app(state: &mut State, area: Rect, ctx: &mut FrameContext) {
         match msg {
             Message::Input(InputMessage::Play) => {
                 console_log(format!(
-                    "Time: {:?}, Ticks: {}",
-                    state.tracker.update_duration, state.tracker.tick_count
+                    "Time: {:.3}, Ticks: {}",
+                    state.tracker.clock.time(),
+                    state.tracker.tick_count
                 ));
-                // state.tracker.play_note();
-                state.tracker.playing = !state.tracker.playing;
+                // When stopped, the focused view decides what to play. In live
+                // mode, play queues chains and only stop stops.
+                if state.tracker.playing && state.tracker.live.is_none() {
+                    state.tracker.stop();
+                    return true;
+                }
+            }
+            Message::Input(InputMessage::Undo) => {
+                state.history.undo(&mut state.tracker);
+                return true;
+            }
+            Message::Input(InputMessage::Redo) => {
+                state.history.redo(&mut state.tracker);
+                return true;
+            }
+            Message::Input(InputMessage::NoteOff(key)) => {
+                if state.audition_key == Some(*key) {
+                    state.audition_key = None;
+                    state.tracker.stop_audition();
+                }
+                return true;
+            }
+            Message::Input(InputMessage::SemitoneUp) => {
+                state.tracker.semi_tone_up();
+                return true;
+            }
+            Message::Input(InputMessage::SemitoneDown) => {
+                state.tracker.semi_tone_down();
+                return true;
+            }
+            Message::Input(InputMessage::OctaveUp) => {
+                state.tracker.octave_up();
+                return true;
+            }
+            Message::Input(InputMessage::OctaveDown) => {
+                state.tracker.octave_down();
+                return true;
+            }
+            Message::Input(InputMessage::Record) => {
+                state.recording = !state.recording;
+                return true;
+            }
+            Message::Input(InputMessage::Stop) => {
+                state.tracker.stop();
+                return true;
+            }
+            Message::Input(InputMessage::PlayMode) => {
+                state.tracker.live = match state.tracker.live {
+                    None => Some(Quantization::Bar),
+                    Some(Quantization::Bar) => Some(Quantization::Phrase),
+                    Some(Quantization::Phrase) => None,
+                };
                 return true;
             }
             Message::Refresh => {
@@ -111,22 +169,114 @@ pub fn render_app(state: &mut State, area: Rect, ctx: &mut FrameContext) {
     let layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(vec![
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
-            Constraint::Percentage(25),
+            Constraint::Length(29),
+            Constraint::Length(11),
+            Constraint::Length(33),
+            Constraint::Length(27),
+            Constraint::Fill(1),
         ])
         .split(layout[1]);
 
-    render_view(mixer_view, state, &mut focus_calculator, layout[0], ctx);
+    let instrument_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Length(19), Constraint::Fill(1)])
+        .split(layout[3]);
+
+    let right_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Length(4),
+            Constraint::Length(9),
+            Constraint::Length(16),
+            Constraint::Fill(1),
+        ])
+        .split(layout[4]);
+
+    let effects_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Length(27), Constraint::Fill(1)])
+        .split(right_layout[2]);
 
-    render_view(effects_view, state, &mut focus_calculator, layout[1], ctx);
+    render_view(song_view, state, &mut focus_calculator, layout[0], ctx);
+
+    render_view(chain_view, state, &mut focus_calculator, layout[1], ctx);
 
     render_view(phrase_view, state, &mut focus_calculator, layout[2], ctx);
 
-    console(layout[3], ctx);
+    render_view(
+        groove_view,
+        state,
+        &mut focus_calculator,
+        instrument_layout[0],
+        ctx,
+    );
+
+    render_view(
+        instrument_view,
+        state,
+        &mut focus_calculator,
+        instrument_layout[1],
+        ctx,
+    );
+
+    render_view(
+        transport_view,
+        state,
+        &mut focus_calculator,
+        right_layout[0],
+        ctx,
+    );
+
+    render_view(
+        mixer_view,
+        state,
+        &mut focus_calculator,
+        right_layout[1],
+        ctx,
+    );
+
+    render_view(
+        effects_view,
+        state,
+        &mut focus_calculator,
+        effects_layout[0],
+        ctx,
+    );
+
+    render_view(
+        generator_view,
+        state,
+        &mut focus_calculator,
+        effects_layout[1],
+        ctx,
+    );
+
+    console(right_layout[3], ctx);
+
+    // Nothing focused handled play, start the song from the song cursor. Notes
+    // not handled audition the instrument being edited.
+    ctx.process_messages(|msg, _msgs| match msg {
+        Message::Input(InputMessage::Play) => {
+            state.tracker.play_song(state.song_focused / NB_TRACKS);
+            true
+        }
+        Message::Input(InputMessage::NoteOn(key)) => {
+            let tone = state.tracker.tone.up(*key as u32);
+            state
+                .tracker
+                .audition(state.track_edited, tone, state.instrument_edited, 1.0);
+            state.audition_key = Some(*key);
+            true
+        }
+        _ => false,
+    });
 
     if let Ok(focus_id) = focus_calculator.to(direction) {
         state.view_focused = focus_id;
     }
+
+    // Holding Edit while changing a value makes a single undo step
+    state
+        .history
+        .record(&state.tracker, state.keyboard.is_pressed(Key::Edit));
 }
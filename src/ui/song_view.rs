@@ -0,0 +1,145 @@
+use crate::{
+    math::to_hex_str_2,
+    tracker::{live::Cue, track::Source, NB_TRACKS},
+    ui::{keyboard::InputMessage, message::Message},
+};
+
+use super::{
+    block::block,
+    chain_view::clone_chain,
+    editable_index::editable_index,
+    focus_calculator::{view_process_focus_message, FocusCalculator},
+    frame_context::FrameContext,
+    label::label,
+    state::State,
+};
+use ratatui::prelude::*;
+
+const SONG_LENGTH: usize = 256;
+
+pub fn song_view(state: &mut State, focused: bool, area: Rect, ctx: &mut FrameContext) {
+    let title = match state.tracker.live {
+        Some(quantization) => format!(" Live {} ", quantization.get_string()),
+        None => " Song ".to_string(),
+    };
+    let inner = block(title.red().bold(), None as Option<&str>, focused, area, ctx);
+
+    // Same scrolling as the phrase view: focus works on the whole song and
+    // only the rows inside the window are drawn.
+    let visible_rows = inner.height.saturating_sub(1) as usize;
+    let focused_row = state.song_focused / NB_TRACKS;
+    if focused_row < state.song_scroll {
+        state.song_scroll = focused_row;
+    } else if focused_row >= state.song_scroll + visible_rows {
+        state.song_scroll = focused_row + 1 - visible_rows;
+    }
+    let scroll = state.song_scroll;
+    let is_visible = |i: usize| i >= scroll && i < scroll + visible_rows;
+    let screen = |rect: Rect, i: usize| Rect {
+        y: inner.y + 1 + i.saturating_sub(scroll) as u16,
+        ..rect
+    };
+
+    state.track_edited = state.song_focused % NB_TRACKS;
+
+    ctx.process_messages(|msg, _msgs| match msg {
+        Message::Input(InputMessage::Play) => {
+            if state.tracker.live.is_some() {
+                // Playing the chain already playing, or an empty cell, stops the track
+                let track_id = state.track_edited;
+                let track = &state.tracker.tracks[track_id];
+                let playing =
+                    track.source == Some(Source::Live) && track.chain_cursor == focused_row;
+                let cue = if playing || track.chains[focused_row].is_none() {
+                    Cue::Stop
+                } else {
+                    Cue::Launch(focused_row)
+                };
+                state.tracker.queue(track_id, cue);
+            } else {
+                state.tracker.play_song(focused_row);
+            }
+            true
+        }
+        _ => false,
+    });
+
+    let mut focus_calculator = FocusCalculator::new(state.song_focused);
+
+    (0..NB_TRACKS).for_each(|t| {
+        label(
+            &format!("T{}", t),
+            Rect::new(inner.x + 3 + t as u16 * 3, inner.y, 2, 1),
+            ctx,
+        );
+    });
+
+    (0..SONG_LENGTH).for_each(|i| {
+        if !is_visible(i) {
+            (0..NB_TRACKS).for_each(|t| {
+                focus_calculator.add(Rect::new(inner.x + 3 + t as u16 * 3, i as u16, 2, 1));
+            });
+            return;
+        }
+
+        label(
+            &to_hex_str_2(i as u8),
+            screen(Rect::new(inner.x, 0, 2, 1), i),
+            ctx,
+        );
+
+        (0..NB_TRACKS).for_each(|t| {
+            let mut value = state.tracker.tracks[t].chains[i];
+
+            let (focused, rect) =
+                focus_calculator.add(Rect::new(inner.x + 3 + t as u16 * 3, i as u16, 2, 1));
+            if focused {
+                if let Some(chain_id) = value {
+                    value = clone_chain(state, chain_id, ctx).or(value);
+                }
+            }
+            editable_index(&mut value, 255, focused, screen(rect, i), ctx);
+
+            state.tracker.tracks[t].chains[i] = value;
+
+            if focused {
+                if let Some(chain_id) = value {
+                    state.chain_edited = chain_id;
+                }
+            }
+        });
+    });
+
+    // Playing chains are marked with a ">", queued ones with a "*" and the ones
+    // stopping at the next boundary with a "x"
+    state
+        .tracker
+        .tracks
+        .iter()
+        .enumerate()
+        .flat_map(|(t, track)| {
+            let playing = matches!(track.source, Some(Source::Song | Source::Live));
+            let marker = match track.cue {
+                Some(Cue::Launch(row)) => Some((row, "*")),
+                _ => None,
+            };
+            let current = playing.then(|| {
+                let symbol = if track.cue == Some(Cue::Stop) {
+                    "x"
+                } else {
+                    ">"
+                };
+                (track.chain_cursor, symbol)
+            });
+            [current, marker].into_iter().flatten().map(move |m| (t, m))
+        })
+        .filter(|(_, (row, _))| is_visible(*row))
+        .for_each(|(t, (row, symbol))| {
+            let rect = screen(Rect::new(inner.x + 2 + t as u16 * 3, 0, 1, 1), row);
+            ctx.add(move |buf| {
+                Line::from(symbol).render(rect, buf);
+            });
+        });
+
+    view_process_focus_message(&mut state.song_focused, &focus_calculator, ctx);
+}
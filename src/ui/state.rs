@@ -1,25 +1,68 @@
-use crate::tracker::Tracker;
+use crate::tracker::{generator::Generator, history::History, Tracker};
 
-use super::keyboard::Keyboard;
+use super::{keyboard::Keyboard, phrase_block::Clipboard};
 
 pub struct State {
     pub keyboard: Keyboard,
     pub tracker: Tracker,
+    pub history: History,
     pub mixer_focused: usize,
+    pub song_focused: usize,
+    pub song_scroll: usize,
     pub effects_focused: usize,
+    pub chain_focused: usize,
     pub phrase_focused: usize,
+    pub phrase_scroll: usize,
+    /// Cell, as (row, column), where the selection in the phrase started
+    pub phrase_selection: Option<(usize, usize)>,
+    pub clipboard: Option<Clipboard>,
+    pub generator: Generator,
+    pub generator_focused: usize,
+    /// Notes played on the keyboard are written into the phrase
+    pub recording: bool,
+    // Piano key of the note being auditioned
+    pub audition_key: Option<u8>,
+    pub groove_focused: usize,
+    pub instrument_focused: usize,
+    pub instrument_scroll: usize,
+    pub transport_focused: usize,
     pub view_focused: usize,
+    pub track_edited: usize,
+    pub chain_edited: usize,
+    pub phrase_edited: usize,
+    pub groove_edited: usize,
+    pub instrument_edited: usize,
 }
 
 impl State {
     pub fn new(tracker: Tracker) -> Self {
         Self {
+            history: History::new(&tracker),
             tracker,
             keyboard: Keyboard::new(),
             mixer_focused: 0,
+            song_focused: 0,
+            song_scroll: 0,
             effects_focused: 0,
+            chain_focused: 0,
             phrase_focused: 0,
+            phrase_scroll: 0,
+            phrase_selection: None,
+            clipboard: None,
+            generator: Generator::new(),
+            generator_focused: 0,
+            recording: false,
+            audition_key: None,
+            groove_focused: 0,
+            instrument_focused: 0,
+            instrument_scroll: 0,
+            transport_focused: 0,
             view_focused: 0,
+            track_edited: 0,
+            chain_edited: 0,
+            phrase_edited: 0,
+            groove_edited: 0,
+            instrument_edited: 0,
         }
     }
 }
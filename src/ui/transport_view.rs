@@ -0,0 +1,65 @@
+use crate::tracker::{MAX_BPM, MIN_BPM};
+
+use super::{
+    block::block,
+    editable_index::editable_index,
+    editable_number::editable_number,
+    focus_calculator::{view_process_focus_message, FocusCalculator},
+    frame_context::FrameContext,
+    label::label,
+    state::State,
+};
+use ratatui::prelude::*;
+
+pub fn transport_view(state: &mut State, focused: bool, area: Rect, ctx: &mut FrameContext) {
+    let inner = block(
+        " Transport ".red().bold(),
+        None as Option<&str>,
+        focused,
+        area,
+        ctx,
+    );
+
+    let mut focus_calculator = FocusCalculator::new(state.transport_focused);
+
+    label("BPM", Rect::new(inner.x, inner.y, 4, 1), ctx);
+    let (focused, rect) = focus_calculator.add(Rect::new(inner.x + 4, inner.y, 3, 1));
+    let mut bpm = state.tracker.bpm as usize;
+    editable_number(&mut bpm, MIN_BPM, MAX_BPM, focused, rect, ctx);
+    state.tracker.set_bpm(bpm as f32);
+
+    label("Tempo", Rect::new(inner.x + 8, inner.y, 6, 1), ctx);
+    label(
+        &format!("{:>3}", state.tracker.tempo.round()),
+        Rect::new(inner.x + 14, inner.y, 3, 1),
+        ctx,
+    );
+
+    label("Seed", Rect::new(inner.x + 18, inner.y, 5, 1), ctx);
+    let (focused, rect) = focus_calculator.add(Rect::new(inner.x + 23, inner.y, 2, 1));
+    let mut value = Some(state.tracker.seed as usize);
+    editable_index(&mut value, 255, focused, rect, ctx);
+    state.tracker.seed = value.unwrap_or(0) as u64;
+
+    let mode = match state.tracker.live {
+        Some(quantization) => format!("Live {}", quantization.get_string()),
+        None => "Song".to_string(),
+    };
+    label("Mode", Rect::new(inner.x, inner.y + 1, 4, 1), ctx);
+    label(&mode, Rect::new(inner.x + 4, inner.y + 1, 12, 1), ctx);
+
+    label("Keys", Rect::new(inner.x + 18, inner.y + 1, 5, 1), ctx);
+    label(
+        &state.tracker.tone.get_string(),
+        Rect::new(inner.x + 23, inner.y + 1, 3, 1),
+        ctx,
+    );
+    if state.recording {
+        let rect = Rect::new(inner.x + 27, inner.y + 1, 3, 1);
+        ctx.add(move |buf| {
+            Line::from("REC".black().on_red()).render(rect, buf);
+        });
+    }
+
+    view_process_focus_message(&mut state.transport_focused, &focus_calculator, ctx);
+}